@@ -1,5 +1,7 @@
 use crate::{
-    auth,
+    auth, config,
+    errors::ParraCliError,
+    http,
     types::{
         api::{
             AppBootstrapResponseBody, ApplicationCollectionResponse,
@@ -11,13 +13,13 @@ use crate::{
         auth::Credential,
     },
 };
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 
 use async_recursion::async_recursion;
+use reqwest::{Method, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
-use std::{collections::HashMap, error::Error};
 
 #[async_recursion(?Send)]
 pub async fn report_event(
@@ -35,15 +37,7 @@ pub async fn report_event(
             None
         };
 
-    let now = SystemTime::now();
-    let duration = now
-        .duration_since(UNIX_EPOCH)
-        .expect("SystemTime before UNIX EPOCH!");
-    let date_time = DateTime::<Utc>::from_timestamp(
-        duration.as_secs() as i64,
-        duration.subsec_nanos(),
-    )
-    .expect("Invalid timestamp");
+    let date_time = Utc::now();
 
     let endpoint = "/tracking/sessions";
     let body = SessionRequest {
@@ -54,11 +48,11 @@ pub async fn report_event(
         }],
     };
 
-    let result: Result<EmptyResponse, Box<dyn Error>> =
+    let result: Result<EmptyResponse, ParraCliError> =
         perform_request_with_body(
             credential.as_ref(),
             &endpoint,
-            reqwest::Method::POST,
+            Method::POST,
             body,
         )
         .await;
@@ -77,7 +71,7 @@ pub async fn report_event(
 
 pub async fn get_tenant(
     tenant_id: &str,
-) -> Result<TenantResponse, Box<dyn Error>> {
+) -> Result<TenantResponse, ParraCliError> {
     // get-tenant-by-id
 
     let authorized_user = ensure_auth().await?;
@@ -90,7 +84,7 @@ pub async fn get_tenant(
     Ok(response)
 }
 
-pub async fn get_tenants() -> Result<Vec<TenantResponse>, Box<dyn Error>> {
+pub async fn get_tenants() -> Result<Vec<TenantResponse>, ParraCliError> {
     // get-tenants-for-user-by-id
 
     let authorized_user = ensure_auth().await?;
@@ -106,7 +100,7 @@ pub async fn get_tenants() -> Result<Vec<TenantResponse>, Box<dyn Error>> {
 pub async fn complete_bootstrap(
     tenant_id: &str,
     application_id: &str,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<(), ParraCliError> {
     let authorized_user = ensure_auth().await?;
 
     let endpoint = format!(
@@ -116,11 +110,11 @@ pub async fn complete_bootstrap(
 
     let body = EmptyRequest {};
 
-    let result: Result<EmptyResponse, Box<dyn Error>> =
+    let result: Result<EmptyResponse, ParraCliError> =
         perform_request_with_body(
             Some(&authorized_user.credential),
             &endpoint,
-            reqwest::Method::POST,
+            Method::POST,
             body,
         )
         .await;
@@ -140,7 +134,7 @@ pub async fn complete_bootstrap(
 
 pub async fn create_tenant(
     name: &str,
-) -> Result<TenantResponse, Box<dyn Error>> {
+) -> Result<TenantResponse, ParraCliError> {
     // create-tenant-for-user-by-id
     let authorized_user = ensure_auth().await?;
 
@@ -153,7 +147,7 @@ pub async fn create_tenant(
     let response: TenantResponse = perform_request_with_body(
         Some(&authorized_user.credential),
         &endpoint,
-        reqwest::Method::POST,
+        Method::POST,
         body,
     )
     .await?;
@@ -163,7 +157,7 @@ pub async fn create_tenant(
 
 pub async fn paginate_applications(
     tenant_id: &str,
-) -> Result<Vec<ApplicationResponse>, Box<dyn Error>> {
+) -> Result<Vec<ApplicationResponse>, ParraCliError> {
     // paginate-applications-for-tenant-by-id
 
     let authorized_user = ensure_auth().await?;
@@ -181,7 +175,7 @@ pub async fn paginate_applications(
 pub async fn get_application(
     tenant_id: &str,
     application_id: &str,
-) -> Result<ApplicationResponse, Box<dyn Error>> {
+) -> Result<ApplicationResponse, ParraCliError> {
     // get-application-by-id-for-tenant-by-id
 
     let authorized_user = ensure_auth().await?;
@@ -199,7 +193,7 @@ pub async fn create_application(
     tenant_id: &str,
     name: &str,
     bundle_id: &str,
-) -> Result<ApplicationResponse, Box<dyn Error>> {
+) -> Result<ApplicationResponse, ParraCliError> {
     // create-application-for-tenant-by-id
 
     let authorized_user = ensure_auth().await?;
@@ -216,7 +210,7 @@ pub async fn create_application(
     let response: ApplicationResponse = perform_request_with_body(
         Some(&authorized_user.credential),
         &endpoint,
-        reqwest::Method::POST,
+        Method::POST,
         body,
     )
     .await?;
@@ -228,7 +222,7 @@ static CURRENT_USER: Mutex<Option<UserResponse>> = Mutex::new(None);
 
 pub async fn get_current_user(
     credential: &Credential,
-) -> Result<UserResponse, Box<dyn Error>> {
+) -> Result<UserResponse, ParraCliError> {
     if let Ok(guard) = CURRENT_USER.lock() {
         if let Some(user) = &*guard {
             return Ok(user.clone());
@@ -245,7 +239,7 @@ pub async fn get_current_user(
     return Ok(response.user);
 }
 
-async fn ensure_auth() -> Result<AuthorizedUser, Box<dyn Error>> {
+async fn ensure_auth() -> Result<AuthorizedUser, ParraCliError> {
     let (credential, _) = auth::perform_device_authentication().await?;
 
     let user = get_current_user(&credential).await?;
@@ -279,7 +273,7 @@ pub async fn post_bootstrap_request(
     tenant_id: &str,
     application_id: &str,
     template: Option<String>,
-) -> Result<AppBootstrapResponseBody, Box<dyn Error>> {
+) -> Result<AppBootstrapResponseBody, ParraCliError> {
     // bootstrap-tenant-application
 
     let authorized_user = ensure_auth().await?;
@@ -292,7 +286,7 @@ pub async fn post_bootstrap_request(
     let response: AppBootstrapResponseBody = perform_request_with_body(
         Some(&authorized_user.credential),
         &endpoint,
-        reqwest::Method::POST,
+        Method::POST,
         BootstrapRequest { template },
     )
     .await?;
@@ -303,79 +297,107 @@ async fn perform_get_request<T: DeserializeOwned>(
     credential: &Credential,
     endpoint: &str,
     query: Vec<(&str, &str)>,
-) -> Result<T, Box<dyn Error>> {
-    let url = format!("https://api.parra.io/v1{}", endpoint);
-    let client = reqwest::Client::new();
-    let token = &credential.token.trim();
-
-    let request = client
-        .request(reqwest::Method::GET, url.clone())
-        .query(&query)
-        .bearer_auth(token);
-
-    let response = request.send().await?;
+) -> Result<T, ParraCliError> {
+    let body =
+        perform_request(Some(credential), endpoint, Method::GET, &query, None)
+            .await?;
 
-    match response.error_for_status() {
-        Ok(_res) => {
-            let body = _res.text().await?;
+    parse_json_response(&body)
+}
 
-            parse_json_response(&body)
-        }
-        Err(err) => {
-            eprintln!(
-                "Error performing request to: {} error: {}",
-                url.clone(),
-                err
-            );
+async fn perform_request_with_body<T: DeserializeOwned, U: Serialize>(
+    credential: Option<&Credential>,
+    endpoint: &str,
+    method: Method,
+    body: U,
+) -> Result<T, ParraCliError> {
+    let json_body = if method != Method::GET {
+        Some(serde_json::to_value(&body)?)
+    } else {
+        None
+    };
 
-            Err(err.into())
-        }
+    let mut body =
+        perform_request(credential, endpoint, method, &[], json_body).await?;
+    if body.is_empty() {
+        body = "{}".to_string();
     }
+
+    parse_json_response(&body)
 }
 
-async fn perform_request_with_body<T: DeserializeOwned, U: Serialize>(
+/// Sends a request to the Parra API, retrying transient failures. If the API
+/// rejects the credential, it is refreshed once and the request is replayed.
+async fn perform_request(
     credential: Option<&Credential>,
     endpoint: &str,
-    method: reqwest::Method,
-    body: U,
-) -> Result<T, Box<dyn Error>> {
-    let url = format!("https://api.parra.io/v1{}", endpoint);
-    let client = reqwest::Client::new();
+    method: Method,
+    query: &[(&str, &str)],
+    body: Option<serde_json::Value>,
+) -> Result<String, ParraCliError> {
+    let url = config::current().api_url(endpoint);
+
+    let send = |token: Option<String>| {
+        let url = &url;
+        let method = &method;
+        let body = &body;
+
+        http::send_with_retry(method, move || {
+            let mut request =
+                http::client().request(method.clone(), url).query(query);
+
+            if let Some(token) = &token {
+                request = request.bearer_auth(token.trim());
+            }
 
-    let mut request = client.request(method.clone(), url);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
 
-    if let Some(credential) = credential {
-        request = request.bearer_auth(&credential.token)
-    }
+            request
+        })
+    };
 
-    if method != reqwest::Method::GET {
-        request = request.json(&body);
-    }
+    let mut response = send(credential.map(|c| c.token.clone())).await?;
 
-    let response = request.send().await?;
+    if response.status() == StatusCode::UNAUTHORIZED {
+        if let Some(credential) = credential {
+            let refreshed =
+                auth::refresh_rejected_credential(credential).await?;
 
-    if !response.status().is_success() {
-        let body = response.text().await?;
-        return Err(format!("Error response received: {}", body).into());
+            response = send(Some(refreshed.token)).await?;
+
+            if response.status() == StatusCode::UNAUTHORIZED {
+                return Err(ParraCliError::Auth(
+                    "The access token was rejected even after it was refreshed. Log in again or provide a new token.".to_string(),
+                ));
+            }
+        }
     }
 
-    let mut body = response.text().await?;
-    if body.is_empty() {
-        body = "{}".to_string();
+    let status = response.status();
+    let body = response.text().await?;
+
+    if !status.is_success() {
+        return Err(ParraCliError::HttpStatus {
+            url,
+            status: status.as_u16(),
+            body,
+        });
     }
 
-    parse_json_response(&body)
+    Ok(body)
 }
 
 fn parse_json_response<T: DeserializeOwned>(
     body: &String,
-) -> Result<T, Box<dyn Error>> {
+) -> Result<T, ParraCliError> {
     match serde_json::from_str::<T>(&body) {
         Ok(result) => Ok(result),
         Err(err) => {
             eprintln!("Error decoding JSON response: {}", err);
 
-            Err(Box::new(err))
+            Err(err.into())
         }
     }
 }
@@ -1,38 +1,122 @@
 use std::fmt::{Debug, Display};
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(version, about, long_about = "")]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Command,
+
+    #[command(flatten)]
+    pub global: GlobalArgs,
+}
+
+/// Options that apply to every command. Each can also be set with an
+/// environment variable or in the config file, in that order of precedence.
+#[derive(Args, Debug)]
+pub struct GlobalArgs {
+    /// Path to the config file. Defaults to parra/config.toml in your user
+    /// config directory. Can also be set with PARRA_CONFIG.
+    #[arg(long = "config", global = true)]
+    pub config_path: Option<String>,
+
+    /// Base URL of the Parra API, including the version path. Can also be set
+    /// with PARRA_API_URL or `api_base_url` in the config file.
+    #[arg(long = "api-url", global = true)]
+    pub api_base_url: Option<String>,
+
+    /// Base URL of the Parra auth server. Can also be set with PARRA_AUTH_URL
+    /// or `auth_base_url` in the config file.
+    #[arg(long = "auth-url", global = true)]
+    pub auth_base_url: Option<String>,
+
+    /// OAuth client ID used to authenticate with the auth server. Can also be
+    /// set with PARRA_AUTH_CLIENT_ID or `auth0_client_id` in the config file.
+    #[arg(long = "auth-client-id", global = true)]
+    pub auth0_client_id: Option<String>,
+
+    /// The named profile to use. Each profile has its own login and default
+    /// tenant and application. Can also be set with PARRA_PROFILE. Defaults to
+    /// the profile selected with `parra profiles use`.
+    #[arg(long = "profile", global = true)]
+    pub profile: Option<String>,
+}
+
+/// What to do when required dependencies like Xcode are missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DependencyPolicy {
+    /// Install the missing dependencies without asking.
+    Install,
+    /// Generate the project anyway and leave installing them to the user.
+    Skip,
+    /// Fail without generating the project.
+    Fail,
 }
 
-#[derive(Debug, Clone, ValueEnum)]
-// converted to/from kebab-case by default, which matches template directory names.
-pub enum TemplateName {
-    Default,
-    Podcast,
-    RevenueCat,
-    ShopifyStorefront,
-    YC,
+/// How Xcode manages signing. Rendered as `CODE_SIGN_STYLE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CodeSignStyle {
+    Automatic,
+    Manual,
 }
 
-impl Display for TemplateName {
+impl Display for CodeSignStyle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let output = match self {
-            TemplateName::Default => "default",
-            TemplateName::Podcast => "podcast",
-            TemplateName::RevenueCat => "revenue-cat",
-            TemplateName::ShopifyStorefront => "shopify-storefront",
-            TemplateName::YC => "yc",
+            CodeSignStyle::Automatic => "Automatic",
+            CodeSignStyle::Manual => "Manual",
         };
 
         write!(f, "{}", output)
     }
 }
 
+/// Answers to the template's inputs, so that they aren't prompted for.
+#[derive(Args, Debug)]
+pub struct TemplateInputArgs {
+    /// An answer to a template input, as <GROUP>.<KEY>=<VALUE>. Can be passed
+    /// more than once, and takes precedence over --inputs.
+    #[arg(long = "input", value_name = "GROUP.KEY=VALUE")]
+    pub inputs: Vec<String>,
+
+    /// A JSON file of answers to template inputs, either keyed by group and
+    /// then by key, or by <GROUP>.<KEY>.
+    #[arg(long = "inputs", value_name = "FILE")]
+    pub inputs_path: Option<String>,
+}
+
+/// Signing settings that replace the defaults of automatic signing with the
+/// "Apple Development" and "Apple Distribution" identities.
+#[derive(Args, Debug)]
+pub struct CodeSigningArgs {
+    /// The Apple developer team ID. Defaults to the team configured for the
+    /// application in the Parra dashboard.
+    #[arg(long = "team-id")]
+    pub team_id: Option<String>,
+
+    /// The signing style for both configurations. Manual signing requires a
+    /// provisioning profile for each configuration.
+    #[arg(value_enum, long = "code-sign-style")]
+    pub style: Option<CodeSignStyle>,
+
+    /// The signing identity for the Debug configuration.
+    #[arg(long = "debug-code-sign-identity")]
+    pub debug_identity: Option<String>,
+
+    /// The signing identity for the Release configuration.
+    #[arg(long = "release-code-sign-identity")]
+    pub release_identity: Option<String>,
+
+    /// The name of the provisioning profile for the Debug configuration.
+    #[arg(long = "debug-provisioning-profile")]
+    pub debug_profile_specifier: Option<String>,
+
+    /// The name of the provisioning profile for the Release configuration.
+    #[arg(long = "release-provisioning-profile")]
+    pub release_profile_specifier: Option<String>,
+}
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct BootstrapCommandArgs {
@@ -55,17 +139,242 @@ pub struct BootstrapCommandArgs {
     #[arg(short = 'p', long = "project-path")]
     pub project_path: Option<String>,
 
-    #[arg(value_enum, long = "template", default_value_t = TemplateName::Default)]
-    pub template_name: TemplateName,
+    /// The template to generate the project from, by directory name. Run
+    /// `parra templates list` to see the available templates. Defaults to
+    /// "default".
+    #[arg(long = "template")]
+    pub template_name: Option<String>,
+
+    /// A TOML, YAML or JSON manifest describing the project to create. Flags
+    /// take precedence over the values in the manifest.
+    #[arg(long = "from")]
+    pub manifest_path: Option<String>,
+
+    /// Never prompt. Every decision is taken from flags or defaults, and the
+    /// command fails if something required is missing. Requires an existing
+    /// login or PARRA_TOKEN. Xcode isn't opened once the project is created.
+    #[arg(short = 'y', long = "yes", visible_alias = "non-interactive")]
+    pub non_interactive: bool,
+
+    /// The name of the workspace to use when --tenant-id isn't provided. An
+    /// existing workspace with this name is used if there is one, otherwise a
+    /// new one is created.
+    #[arg(long = "tenant-name", conflicts_with = "tenant_id")]
+    pub tenant_name: Option<String>,
+
+    /// The name of the application to use when --application-id isn't
+    /// provided. An existing application with this name is used if there is
+    /// one, otherwise a new one is created.
+    #[arg(long = "app-name", conflicts_with = "application_id")]
+    pub application_name: Option<String>,
+
+    /// The bundle ID for a newly created application. Defaults to
+    /// com.<workspace>.<app name>.
+    #[arg(long = "bundle-id")]
+    pub bundle_id: Option<String>,
+
+    /// Replace the project directory if it already exists instead of asking.
+    #[arg(long = "overwrite")]
+    pub overwrite: bool,
+
+    /// What to do when dependencies like Xcode are missing. If this isn't
+    /// provided, you will be asked whether to install them, or the command
+    /// fails when running with --yes.
+    #[arg(value_enum, long = "dependencies")]
+    pub dependency_policy: Option<DependencyPolicy>,
+
+    /// Render the project into a temporary directory and print the resolved
+    /// context and file tree instead of generating it. No workspace or
+    /// application is created, and the project path isn't touched.
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// The app's marketing version, e.g. 1.2.0. Up to three period separated
+    /// integers. Defaults to 1.0.0.
+    #[arg(long = "marketing-version")]
+    pub marketing_version: Option<String>,
+
+    /// The app's build number. Up to three period separated integers.
+    /// Defaults to 1.
+    #[arg(long = "build-number")]
+    pub build_number: Option<String>,
+
+    /// The minimum iOS version the app supports, e.g. 17.0. Must be at least
+    /// the minimum supported by the template, which is also the default.
+    #[arg(long = "deployment-target")]
+    pub deployment_target: Option<String>,
+
+    #[command(flatten)]
+    pub code_signing: CodeSigningArgs,
+
+    #[command(flatten)]
+    pub template_inputs: TemplateInputArgs,
+
+    /// Where to load templates from instead of the templates published with
+    /// this version of the CLI. Either a local directory, a git URL with an
+    /// optional #<BRANCH|TAG|COMMIT> suffix, or a .tar.gz archive at a URL or
+    /// path. The source can be a single template or a directory of them.
+    #[arg(long = "template-source", value_name = "SOURCE")]
+    pub template_source: Option<String>,
 }
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-pub struct LoginCommandArgs {}
+pub struct RenderCommandArgs {
+    /// The template directory to render, e.g. templates/default.
+    pub template_dir: String,
+
+    /// A JSON file containing the project context, in the same shape that
+    /// bootstrap passes to templates.
+    #[arg(short = 'c', long = "context")]
+    pub context_path: String,
+
+    /// The directory to render the project into.
+    #[arg(short = 'o', long = "output")]
+    pub output_dir: String,
+
+    /// Use the template's local package definitions instead of the remote
+    /// ones.
+    #[arg(long = "local-packages")]
+    pub use_local_packages: bool,
+
+    /// Replace the output directory if it isn't empty.
+    #[arg(long = "overwrite")]
+    pub overwrite: bool,
+
+    #[command(flatten)]
+    pub template_inputs: TemplateInputArgs,
+}
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-pub struct LogoutCommandArgs {}
+pub struct TemplateCommandArgs {
+    #[command(subcommand)]
+    pub command: TemplateCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommand {
+    /// Checks a template for liquid syntax errors, variables that aren't part
+    /// of the project context, an invalid config.json and broken symlinks.
+    Lint(TemplateLintArgs),
+    /// Lists the templates that bootstrap can generate, with their
+    /// descriptions and the tabs they generate.
+    List(TemplateListArgs),
+    /// Manages the templates cached from remote template sources.
+    Cache(TemplateCacheArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct TemplateListArgs {
+    /// Where to load templates from instead of the templates published with
+    /// this version of the CLI. Accepts the same sources as `parra bootstrap
+    /// --template-source`.
+    #[arg(long = "template-source", value_name = "SOURCE")]
+    pub template_source: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct TemplateLintArgs {
+    /// The template directory to check, e.g. templates/default.
+    pub template_dir: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct TemplateCacheArgs {
+    #[command(subcommand)]
+    pub command: TemplateCacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateCacheCommand {
+    /// Lists the cached template sources, when they were fetched and whether
+    /// they've been modified since.
+    List,
+    /// Removes every cached template. They're downloaded again the next time
+    /// they're used.
+    Clear,
+}
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct LoginCommandArgs {
+    /// A pre-issued access or refresh token to log in with instead of the
+    /// browser based device flow. Intended for CI and other headless
+    /// environments. To use a token for a single command without storing it,
+    /// set the PARRA_TOKEN environment variable instead.
+    #[arg(long = "token")]
+    pub token: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct LogoutCommandArgs {
+    /// Log out of every profile instead of only the selected one.
+    #[arg(long = "all-profiles")]
+    pub all_profiles: bool,
+}
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct WhoamiCommandArgs {
+    /// Print the status as JSON instead of human readable text.
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct AuthCommandArgs {
+    #[command(subcommand)]
+    pub command: AuthCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommand {
+    /// Shows the current session. Equivalent to `parra whoami`.
+    Status(WhoamiCommandArgs),
+}
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct ProfilesCommandArgs {
+    #[command(subcommand)]
+    pub command: ProfilesCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfilesCommand {
+    /// Lists all known profiles and marks the active one.
+    List,
+    /// Makes a profile the default for subsequent commands, creating it if it
+    /// doesn't exist yet.
+    Use(ProfileUseArgs),
+    /// Removes a profile and its stored login.
+    Remove(ProfileRemoveArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ProfileUseArgs {
+    /// The name of the profile.
+    pub name: String,
+
+    /// The tenant that bootstrap should use for this profile when --tenant-id
+    /// isn't provided.
+    #[arg(short = 't', long = "tenant-id")]
+    pub tenant_id: Option<String>,
+
+    /// The application that bootstrap should use for this profile when
+    /// --application-id isn't provided.
+    #[arg(short = 'a', long = "application-id")]
+    pub application_id: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ProfileRemoveArgs {
+    /// The name of the profile.
+    pub name: String,
+}
 
 #[derive(Subcommand)]
 pub enum Command {
@@ -74,20 +383,42 @@ pub enum Command {
     /// the directory of your choice. Once complete, you can open the project in
     /// Xcode and start building your app.
     Bootstrap(BootstrapCommandArgs),
+    /// Renders a local template into a directory using a context from a JSON
+    /// file. Doesn't require logging in, and doesn't run xcodegen or resolve
+    /// packages, which makes it suitable for testing templates on CI.
+    Render(RenderCommandArgs),
+    /// Tools for developing templates and managing the template cache.
+    #[command(visible_alias = "templates")]
+    Template(TemplateCommandArgs),
     /// Authenticates with the Parra API using a device auth flow. You will be asked
     /// to open a page in the browser and perform a login to the Parra dashboard.
+    /// Pass --token to log in with a pre-issued token instead.
     Login(LoginCommandArgs),
-    /// Clears Parra authentication tokens from your local state. Subsequent commands
-    /// that require authentication will prompt you to log in again.
+    /// Revokes your refresh token and clears Parra authentication tokens from your
+    /// local state. Subsequent commands that require authentication will prompt
+    /// you to log in again.
     Logout(LogoutCommandArgs),
+    /// Manages named profiles, which let you keep multiple Parra accounts
+    /// logged in on the same machine.
+    Profiles(ProfilesCommandArgs),
+    /// Shows the currently logged in user, when their token expires and
+    /// whether it needs to be refreshed. Never starts a login.
+    Whoami(WhoamiCommandArgs),
+    /// Inspects the current authentication state.
+    Auth(AuthCommandArgs),
 }
 
 impl Display for Command {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let output = match self {
             Command::Bootstrap(_) => "bootstrap",
+            Command::Render(_) => "render",
+            Command::Template(_) => "template",
             Command::Login(_) => "login",
             Command::Logout(_) => "logout",
+            Command::Profiles(_) => "profiles",
+            Command::Whoami(_) => "whoami",
+            Command::Auth(_) => "auth",
         };
 
         write!(f, "{}", output)
@@ -1,46 +1,161 @@
 use crate::api;
+use crate::config;
+use crate::credential_store;
+use crate::errors::ParraCliError;
+use crate::http;
+use crate::profiles;
 use crate::types::auth::{
-    AuthResponse, Credential, DeviceAuthResponse, RefreshResponse, TokenRequest,
+    AccessTokenClaims, AuthResponse, Credential, DeviceAuthResponse,
+    RefreshResponse, TokenRequest,
 };
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use inquire::Confirm;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
-use std::error::Error;
+use std::env;
 use std::ops::Add;
+use std::sync::Mutex;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 const SERVICE_NAME: &str = "parra_cli";
-const AUTH0_CLIENT_ID: &str = "nD9GTUvvqCT0oWi34L2IdJiK0YjupSjY";
 
-pub fn logout() -> Result<bool, Box<dyn Error>> {
-    let result = security_framework::passwords::delete_generic_password(
-        SERVICE_NAME,
-        AUTH0_CLIENT_ID,
+/// Environment variable containing a pre-issued access or refresh token. When
+/// present, it is used instead of any persisted credential and the device
+/// authorization flow is never started.
+pub const TOKEN_ENV_VAR: &str = "PARRA_TOKEN";
+
+/// The credential derived from `PARRA_TOKEN`, cached so that a refresh token is
+/// only exchanged once per invocation.
+static TOKEN_CREDENTIAL: Mutex<Option<Credential>> = Mutex::new(None);
+
+/// The credential that was last refreshed after the API rejected one, so that
+/// later requests in this invocation use it rather than refreshing again.
+static REFRESHED_CREDENTIAL: Mutex<Option<Credential>> = Mutex::new(None);
+
+/// The result of logging out of a single profile. Revoking the refresh token
+/// on the auth server and deleting the local credential are attempted
+/// independently, so that a failure of one doesn't prevent the other.
+pub struct LogoutOutcome {
+    /// `None` when there was no refresh token to revoke.
+    pub revocation: Option<Result<(), ParraCliError>>,
+    /// Whether a local credential existed and was deleted.
+    pub deletion: Result<bool, ParraCliError>,
+}
+
+pub async fn logout_profile(profile: &str) -> LogoutOutcome {
+    let revocation = match get_persisted_credential_for_profile(profile) {
+        Ok(Some(credential)) if !credential.refresh_token.is_empty() => {
+            Some(revoke_refresh_token(&credential.refresh_token).await)
+        }
+        Ok(_) => None,
+        Err(error) => Some(Err(error)),
+    };
+
+    let deletion = delete_persisted_credential(profile);
+
+    LogoutOutcome {
+        revocation,
+        deletion,
+    }
+}
+
+fn delete_persisted_credential(profile: &str) -> Result<bool, ParraCliError> {
+    let store = credential_store::open_credential_store(SERVICE_NAME)?;
+    let account = profiles::credential_account(
+        &config::current().auth0_client_id,
+        profile,
     );
 
-    match result {
-        Ok(_) => Ok(true),
+    store.delete(&account)
+}
 
-        Err(error) => {
-            if error.code() == security_framework_sys::base::errSecItemNotFound
-            {
-                Ok(false)
-            } else {
-                Err(error.into())
-            }
-        }
+async fn revoke_refresh_token(
+    refresh_token: &str,
+) -> Result<(), ParraCliError> {
+    let config = config::current();
+
+    let response = http::client()
+        .post(config.auth_url("/oauth/revoke"))
+        .form(&[
+            ("client_id", config.auth0_client_id.as_str()),
+            ("token", refresh_token),
+        ])
+        .send()
+        .await?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(ParraCliError::HttpStatus {
+            url: config.auth_url("/oauth/revoke"),
+            status: status.as_u16(),
+            body: response.text().await?,
+        })
     }
 }
 
+/// Returns the token provided through `PARRA_TOKEN`, if any.
+pub fn get_env_token() -> Option<String> {
+    env::var(TOKEN_ENV_VAR)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+}
+
+/// Returns the credential that would be used for the current invocation
+/// without refreshing it or ever starting the device authorization flow.
+pub async fn get_existing_credential(
+) -> Result<Option<Credential>, ParraCliError> {
+    if let Some(credential) = get_refreshed_credential() {
+        return Ok(Some(credential));
+    }
+
+    if let Some(token) = get_env_token() {
+        return Ok(Some(get_env_token_credential(&token).await?));
+    }
+
+    get_persisted_credential_for_profile(&config::current().profile)
+}
+
+/// Whether the credential's access token is either already expired or about
+/// to expire, and would be refreshed before its next use.
+pub fn is_refresh_required(
+    credential: &Credential,
+) -> Result<bool, ParraCliError> {
+    let timestamp = current_timestamp()?.as_secs();
+
+    Ok(timestamp > credential.expiry.saturating_sub(30))
+}
+
+fn current_timestamp() -> Result<Duration, ParraCliError> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|error| {
+            ParraCliError::Auth(format!("Invalid system clock: {}", error))
+        })
+}
+
 pub async fn perform_device_authentication(
-) -> Result<(Credential, bool), Box<dyn Error>> {
+) -> Result<(Credential, bool), ParraCliError> {
+    if let Some(credential) = get_refreshed_credential() {
+        if !is_refresh_required(&credential)? {
+            return Ok((credential, false));
+        }
+    }
+
+    if let Some(token) = get_env_token() {
+        return Ok((get_env_token_credential(&token).await?, false));
+    }
+
     match get_persisted_credential() {
         Ok(credential) => {
-            let now = SystemTime::now();
-            let timestamp = now.duration_since(UNIX_EPOCH)?.as_secs();
+            if is_refresh_required(&credential)? {
+                if credential.refresh_token.is_empty() {
+                    return Err(ParraCliError::Auth("The stored access token has expired and can not be refreshed. Log in again or provide a new token.".to_string()));
+                }
 
-            // Token is either already expired or about to expire
-            if timestamp > credential.expiry - 30 {
                 return Ok((
                     perform_refresh_authentication(&credential).await?,
                     false,
@@ -55,9 +170,64 @@ pub async fn perform_device_authentication(
     }
 }
 
+/// Refreshes a credential that the API rejected even though it hadn't
+/// expired yet, e.g. because it was revoked or the clock is off. The refreshed
+/// credential replaces the one it was derived from.
+pub async fn refresh_rejected_credential(
+    credential: &Credential,
+) -> Result<Credential, ParraCliError> {
+    if credential.refresh_token.is_empty() {
+        return Err(ParraCliError::Auth("The access token was rejected and can not be refreshed. Log in again or provide a new token.".to_string()));
+    }
+
+    let refreshed = if get_env_token().is_some() {
+        let refreshed = refresh_without_storing(credential).await?;
+
+        if let Ok(mut guard) = TOKEN_CREDENTIAL.lock() {
+            *guard = Some(refreshed.clone());
+        }
+
+        refreshed
+    } else {
+        perform_refresh_authentication(credential).await?
+    };
+
+    if let Ok(mut guard) = REFRESHED_CREDENTIAL.lock() {
+        *guard = Some(refreshed.clone());
+    }
+
+    Ok(refreshed)
+}
+
+fn get_refreshed_credential() -> Option<Credential> {
+    return REFRESHED_CREDENTIAL
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone());
+}
+
+/// Exchanges the credential's refresh token for a new access token, without
+/// persisting the result.
+async fn refresh_without_storing(
+    credential: &Credential,
+) -> Result<Credential, ParraCliError> {
+    let refresh_response =
+        request_token_refresh(&credential.refresh_token).await?;
+    let expiry = current_timestamp()?
+        .add(Duration::from_secs(refresh_response.expires_in));
+
+    Ok(Credential {
+        refresh_token: refresh_response
+            .refresh_token
+            .unwrap_or(credential.refresh_token.clone()),
+        token: refresh_response.access_token,
+        expiry: expiry.as_secs(),
+    })
+}
+
 async fn perform_refresh_authentication(
     credential: &Credential,
-) -> Result<Credential, Box<dyn Error>> {
+) -> Result<Credential, ParraCliError> {
     let _ = api::report_event(
         "cli_auth_started",
         Some(HashMap::from([("is_reauthenticating", "true")])),
@@ -87,26 +257,117 @@ async fn perform_refresh_authentication(
 
 async fn _perform_refresh_authentication(
     credential: &Credential,
-) -> Result<Credential, Box<dyn Error>> {
-    let result: Result<RefreshResponse, Box<dyn Error>> = post_form_request(
-        "https://auth.parra.io/oauth/token",
+) -> Result<Credential, ParraCliError> {
+    let refresh_response =
+        request_token_refresh(&credential.refresh_token).await?;
+
+    return persist_refresh_credential(&refresh_response, &credential);
+}
+
+async fn request_token_refresh(
+    refresh_token: &str,
+) -> Result<RefreshResponse, ParraCliError> {
+    let config = config::current();
+
+    post_form_request(
+        &config.auth_url("/oauth/token"),
         vec![
-            ("client_id".to_string(), AUTH0_CLIENT_ID.to_string()),
-            (
-                "refresh_token".to_string(),
-                credential.refresh_token.to_string(),
-            ),
+            ("client_id".to_string(), config.auth0_client_id.clone()),
+            ("refresh_token".to_string(), refresh_token.to_string()),
             ("grant_type".to_string(), "refresh_token".to_string()),
         ],
     )
-    .await;
+    .await
+}
 
-    let refresh_response = result?;
+/// Persists a credential built from a pre-issued token, for use with
+/// `parra login --token`. Subsequent commands will use it exactly like one
+/// obtained through the device authorization flow.
+pub async fn login_with_token(
+    token: &str,
+) -> Result<Credential, ParraCliError> {
+    let credential = credential_from_token(token).await?;
 
-    return persist_refresh_credential(&refresh_response, &credential);
+    let store = credential_store::open_credential_store(SERVICE_NAME)?;
+    store.set(&config::current().credential_account(), &credential)?;
+
+    Ok(credential)
+}
+
+async fn get_env_token_credential(
+    token: &str,
+) -> Result<Credential, ParraCliError> {
+    if let Ok(guard) = TOKEN_CREDENTIAL.lock() {
+        if let Some(credential) = &*guard {
+            return Ok(credential.clone());
+        }
+    }
+
+    let credential = credential_from_token(token).await?;
+
+    if let Ok(mut guard) = TOKEN_CREDENTIAL.lock() {
+        *guard = Some(credential.clone());
+    }
+
+    Ok(credential)
 }
 
-async fn perform_normal_authentication() -> Result<Credential, Box<dyn Error>> {
+/// Access tokens are JWTs and are used as-is until their `exp` claim. Anything
+/// else is treated as a refresh token and exchanged for an access token.
+async fn credential_from_token(
+    token: &str,
+) -> Result<Credential, ParraCliError> {
+    let token = token.trim();
+
+    if let Some(claims) = decode_access_token_claims(token) {
+        let now = current_timestamp()?.as_secs();
+
+        if claims.exp <= now {
+            return Err(ParraCliError::Auth(
+                "The provided access token has expired.".to_string(),
+            ));
+        }
+
+        return Ok(Credential {
+            token: token.to_string(),
+            refresh_token: "".to_string(),
+            expiry: claims.exp,
+        });
+    }
+
+    let refresh_response =
+        request_token_refresh(token).await.map_err(|err| match err {
+            ParraCliError::HttpStatus { body, .. } => ParraCliError::Auth(format!(
+                "The provided token is not a valid access token and could not be used as a refresh token: {}",
+                body
+            )),
+            other => other,
+        })?;
+
+    let now = current_timestamp()?;
+    let expiry = now.add(Duration::from_secs(refresh_response.expires_in));
+
+    Ok(Credential {
+        refresh_token: refresh_response
+            .refresh_token
+            .unwrap_or(token.to_string()),
+        token: refresh_response.access_token,
+        expiry: expiry.as_secs(),
+    })
+}
+
+fn decode_access_token_claims(token: &str) -> Option<AccessTokenClaims> {
+    let segments: Vec<&str> = token.split('.').collect();
+    let [_, payload, _] = segments[..] else {
+        return None;
+    };
+
+    let decoded = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+
+    serde_json::from_slice::<AccessTokenClaims>(&decoded).ok()
+}
+
+async fn perform_normal_authentication() -> Result<Credential, ParraCliError> {
     let _ = api::report_event(
         "cli_auth_started",
         Some(HashMap::from([("is_reauthenticating", "false")])),
@@ -134,21 +395,18 @@ async fn perform_normal_authentication() -> Result<Credential, Box<dyn Error>> {
     }
 }
 
-async fn _perform_normal_authentication() -> Result<Credential, Box<dyn Error>>
-{
-    let device_code_url = "https://auth.parra.io/oauth/device/code";
-
-    let device_auth_response: Result<DeviceAuthResponse, Box<dyn Error>> =
-        post_form_request(
-            device_code_url,
-            vec![
-                ("client_id".to_string(), AUTH0_CLIENT_ID.to_string()),
-                ("scope".to_string(), "offline_access".to_string()),
-            ],
-        )
-        .await;
+async fn _perform_normal_authentication() -> Result<Credential, ParraCliError> {
+    let config = config::current();
+    let device_code_url = config.auth_url("/oauth/device/code");
 
-    let device_auth = device_auth_response.unwrap();
+    let device_auth: DeviceAuthResponse = post_form_request(
+        &device_code_url,
+        vec![
+            ("client_id".to_string(), config.auth0_client_id.clone()),
+            ("scope".to_string(), "offline_access".to_string()),
+        ],
+    )
+    .await?;
 
     // confirm that the user wants to open the browser
     let confirm_message =
@@ -164,7 +422,9 @@ async fn _perform_normal_authentication() -> Result<Credential, Box<dyn Error>>
         .prompt()?;
 
     if !confirmed {
-        return Err("Authentication cancelled".into());
+        return Err(ParraCliError::UserCancelled(
+            "Authentication cancelled".to_string(),
+        ));
     }
 
     let result = open::that(device_auth.verification_uri_complete);
@@ -180,10 +440,10 @@ async fn _perform_normal_authentication() -> Result<Credential, Box<dyn Error>>
     }
 
     // begin polling for the token
-    let token_url = "https://auth.parra.io/oauth/token";
+    let token_url = config.auth_url("/oauth/token");
 
     let token_request_body = TokenRequest {
-        client_id: AUTH0_CLIENT_ID.to_string(),
+        client_id: config.auth0_client_id.clone(),
         device_code: device_auth.device_code,
         grant_type: "urn:ietf:params:oauth:grant-type:device_code".to_string(),
     };
@@ -193,7 +453,7 @@ async fn _perform_normal_authentication() -> Result<Credential, Box<dyn Error>>
     // aren't awaiting the result of that web page being opened, we can launch it, then
     // begin polling.
     let poll_result = poll_for_token::<AuthResponse>(
-        token_url,
+        &token_url,
         device_auth.interval,
         device_auth.expires_in,
         token_request_body,
@@ -209,24 +469,37 @@ async fn _perform_normal_authentication() -> Result<Credential, Box<dyn Error>>
     );
 }
 
-pub fn get_persisted_credential() -> Result<Credential, Box<dyn Error>> {
-    let data = security_framework::passwords::get_generic_password(
-        SERVICE_NAME,
-        AUTH0_CLIENT_ID,
-    )?;
+pub fn get_persisted_credential() -> Result<Credential, ParraCliError> {
+    match get_persisted_credential_for_profile(&config::current().profile)? {
+        Some(credential) => Ok(credential),
+        None => Err(ParraCliError::Auth(
+            "No persisted credential found".to_string(),
+        )),
+    }
+}
 
-    let data = String::from_utf8(data)?;
+pub fn get_persisted_credential_for_profile(
+    profile: &str,
+) -> Result<Option<Credential>, ParraCliError> {
+    let store = credential_store::open_credential_store(SERVICE_NAME)?;
+    let account = profiles::credential_account(
+        &config::current().auth0_client_id,
+        profile,
+    );
 
-    return Ok(serde_json::from_str::<Credential>(&data)?);
+    store.get(&account)
 }
 
 fn persist_refresh_credential(
     data: &RefreshResponse,
     existing_credential: &Credential,
-) -> Result<Credential, Box<dyn Error>> {
+) -> Result<Credential, ParraCliError> {
     let next_credential = Credential {
         token: data.access_token.clone(),
-        refresh_token: existing_credential.refresh_token.clone(),
+        refresh_token: data
+            .refresh_token
+            .clone()
+            .unwrap_or(existing_credential.refresh_token.clone()),
         expiry: existing_credential.expiry,
     };
 
@@ -241,9 +514,8 @@ fn persist_credential(
     access_token: &str,
     expires_in: u64,
     refresh_token: &str,
-) -> Result<Credential, Box<dyn Error>> {
-    let now = SystemTime::now();
-    let mut expiry = now.duration_since(UNIX_EPOCH)?;
+) -> Result<Credential, ParraCliError> {
+    let mut expiry = current_timestamp()?;
     expiry = expiry.add(Duration::from_secs(expires_in));
 
     let credential = Credential {
@@ -252,13 +524,8 @@ fn persist_credential(
         refresh_token: refresh_token.to_string(),
     };
 
-    let serialized = serde_json::to_string(&credential).unwrap();
-
-    security_framework::passwords::set_generic_password(
-        SERVICE_NAME,
-        AUTH0_CLIENT_ID,
-        serialized.as_bytes(),
-    )?;
+    let store = credential_store::open_credential_store(SERVICE_NAME)?;
+    store.set(&config::current().credential_account(), &credential)?;
 
     Ok(credential)
 }
@@ -266,9 +533,8 @@ fn persist_credential(
 async fn post_form_request<T: DeserializeOwned>(
     url: &str,
     fields: Vec<(String, String)>,
-) -> Result<T, Box<dyn Error>> {
-    let client = reqwest::Client::new();
-    let response = client.post(url).form(&fields).send().await?;
+) -> Result<T, ParraCliError> {
+    let response = http::client().post(url).form(&fields).send().await?;
 
     let status = response.status();
     let body = response.text().await?;
@@ -276,8 +542,11 @@ async fn post_form_request<T: DeserializeOwned>(
     if status.is_success() {
         return Ok(serde_json::from_str::<T>(&body)?);
     } else {
-        eprintln!("Request failed with status {}: {}", status, body);
-        return Err("Request failed".into());
+        return Err(ParraCliError::HttpStatus {
+            url: url.to_string(),
+            status: status.as_u16(),
+            body,
+        });
     }
 }
 
@@ -286,19 +555,21 @@ async fn poll_for_token<T: DeserializeOwned>(
     interval: u64,
     expires_in: u64,
     body: TokenRequest,
-) -> Result<T, Box<dyn Error>> {
+) -> Result<T, ParraCliError> {
     let interval = Duration::from_secs(interval);
     let start_time = Instant::now();
     let expires_in = Duration::from_secs(expires_in);
 
-    let client = reqwest::Client::new();
+    let client = http::client();
 
     loop {
         // spec says to wait for the interval before the first poll
         async_std::task::sleep(interval).await;
 
         if start_time.elapsed() >= expires_in {
-            return Err("Parra sign in request has expired. Try again.".into());
+            return Err(ParraCliError::Auth(
+                "Parra sign in request has expired. Try again.".to_string(),
+            ));
         }
 
         let response = client.post(url).json(&body).send().await?;
@@ -310,12 +581,11 @@ async fn poll_for_token<T: DeserializeOwned>(
         } else if status.as_u16() == 403 {
             println!("Waiting for authorization from the browser...");
         } else {
-            eprintln!(
-                "Check for authorization token failed unexpectedly {}: {}",
-                status, body
-            );
-
-            return Err("Request failed".into());
+            return Err(ParraCliError::HttpStatus {
+                url: url.to_string(),
+                status: status.as_u16(),
+                body,
+            });
         }
     }
 }
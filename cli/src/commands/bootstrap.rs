@@ -1,7 +1,18 @@
-use crate::constants::built::{self, built_info};
+use crate::arg_parser::{CodeSignStyle, CodeSigningArgs, DependencyPolicy};
+use crate::constants::built;
 use crate::dependencies::DerivedDependency;
+use crate::errors::ParraCliError;
+use crate::manifest::{
+    self, ManifestCodeSigning, ManifestSigningConfiguration,
+};
+use crate::project_generator::generator::ExistingProjectPolicy;
+use crate::template_source::{
+    self, FetchedTemplates, ResolvedTemplate, TempDir, TemplateSource,
+    TemplateSummary,
+};
 use crate::types::api::{
-    ApplicationResponse, TenantDomain, TenantDomainType, TenantResponse,
+    ApplicationIosConfig, ApplicationResponse, TenantDomain, TenantDomainType,
+    TenantResponse,
 };
 use crate::types::color_scheme::get_supported_parra_colored_color_scheme;
 use crate::types::dependency::SemanticVersion;
@@ -10,7 +21,8 @@ use crate::types::templates::{
     CodeSigningConfig, CodeSigningConfigs, ProjectContext, SdkContextInfo,
     TemplateConfig, TemplateInfo, TenantContextInfo,
 };
-use crate::{api, dependencies, project_generator};
+use crate::{api, auth, dependencies, profiles, project_generator};
+use clap::ValueEnum;
 use colored::Colorize;
 use convert_case::{Case, Casing};
 use git2::Repository;
@@ -18,15 +30,14 @@ use inquire::validator::{MaxLengthValidator, MinLengthValidator, Validation};
 use inquire::{Confirm, InquireError, Select, Text};
 use regex::Regex;
 use slugify::slugify;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env::{self};
-use std::error::Error;
 use std::fmt::Display;
-use std::fs::{self, read_to_string};
-use std::io;
+use std::fs;
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
-use std::process::{exit, Command};
-use std::str::FromStr;
+use std::process::Command;
+use walkdir::WalkDir;
 
 static MIN_XCODE_VERSION: SemanticVersion = SemanticVersion {
     major: 15,
@@ -40,12 +51,330 @@ static DESIRED_XCODE_VERSION: SemanticVersion = SemanticVersion {
     patch: 0,
 };
 
+/// Stands in for the ID of a workspace or application that a dry run would
+/// have created.
+const DRY_RUN_PLACEHOLDER_ID: &str = "00000000-0000-0000-0000-000000000000";
+
+/// A workspace or application that bootstrap will use.
+#[derive(Debug, Clone)]
+enum Resolved<T> {
+    /// Exists in Parra, whether it already did or was just created.
+    Existing(T),
+    /// Would have been created, but a dry run only pretended to, so it
+    /// doesn't exist in Parra and can't be used in API requests.
+    DryRunPlaceholder(T),
+}
+
+impl<T> Resolved<T> {
+    fn is_placeholder(&self) -> bool {
+        return matches!(self, Resolved::DryRunPlaceholder(_));
+    }
+
+    fn into_inner(self) -> T {
+        match self {
+            Resolved::Existing(value) | Resolved::DryRunPlaceholder(value) => {
+                value
+            }
+        }
+    }
+}
+
+impl<T> Deref for Resolved<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Resolved::Existing(value) | Resolved::DryRunPlaceholder(value) => {
+                value
+            }
+        }
+    }
+}
+
+/// Used when a template doesn't declare a minimum deployment target.
+const DEFAULT_DEPLOYMENT_TARGET: &str = "17.0";
+
 static DESIRED_IOS_RUNTIME_VERSION: SemanticVersion = SemanticVersion {
     major: 17,
     minor: 5,
     patch: 0,
 };
 
+/// Everything `parra bootstrap` needs to know up front. Anything left unset is
+/// taken from the manifest if there is one, and is otherwise prompted for
+/// unless `non_interactive` is set.
+#[derive(Debug, Default)]
+pub struct BootstrapOptions {
+    pub application_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub project_path: Option<String>,
+    pub template_name: Option<String>,
+    pub manifest_path: Option<PathBuf>,
+    pub non_interactive: bool,
+    pub tenant_name: Option<String>,
+    pub application_name: Option<String>,
+    pub bundle_id: Option<String>,
+    pub overwrite: bool,
+    pub dependency_policy: Option<DependencyPolicy>,
+    /// Render the project to a temporary directory and print it instead of
+    /// generating it. Nothing is created in Parra or at the project path.
+    pub dry_run: bool,
+    pub marketing_version: Option<String>,
+    pub build_number: Option<String>,
+    pub deployment_target: Option<String>,
+    pub code_signing: CodeSigningOptions,
+    /// Answers to template inputs keyed by `<group>.<key>`. Inputs with an
+    /// answer aren't prompted for.
+    pub input_answers: BTreeMap<String, String>,
+    /// Answers from `--input` flags, as `<group>.<key>=<value>`.
+    pub inputs: Vec<String>,
+    /// A JSON file of answers from `--inputs`.
+    pub inputs_path: Option<PathBuf>,
+    /// Where to load templates from instead of the templates published with
+    /// this version of the CLI. See `TemplateSource::parse`.
+    pub template_source: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct CodeSigningOptions {
+    pub team_id: Option<String>,
+    /// Applies to both configurations unless they override it.
+    pub style: Option<CodeSignStyle>,
+    pub debug: SigningConfigurationOptions,
+    pub release: SigningConfigurationOptions,
+}
+
+#[derive(Debug, Default)]
+pub struct SigningConfigurationOptions {
+    pub identity: Option<String>,
+    pub style: Option<CodeSignStyle>,
+    pub profile_specifier: Option<String>,
+}
+
+impl From<CodeSigningArgs> for CodeSigningOptions {
+    fn from(args: CodeSigningArgs) -> Self {
+        CodeSigningOptions {
+            team_id: args.team_id,
+            style: args.style,
+            debug: SigningConfigurationOptions {
+                identity: args.debug_identity,
+                style: None,
+                profile_specifier: args.debug_profile_specifier,
+            },
+            release: SigningConfigurationOptions {
+                identity: args.release_identity,
+                style: None,
+                profile_specifier: args.release_profile_specifier,
+            },
+        }
+    }
+}
+
+impl BootstrapOptions {
+    /// Fills in everything that wasn't provided as a flag from the manifest at
+    /// `manifest_path`, if there is one.
+    fn apply_manifest(&mut self) -> Result<(), ParraCliError> {
+        let Some(manifest_path) = &self.manifest_path else {
+            return Ok(());
+        };
+
+        let manifest = manifest::read_manifest(manifest_path)?;
+
+        // A workspace or application given as a flag replaces the one in the
+        // manifest entirely, whether it was given by ID or by name.
+        if self.tenant_id.is_none() && self.tenant_name.is_none() {
+            if let Some(tenant) = manifest.tenant.as_ref() {
+                self.tenant_id = tenant.id.clone();
+                self.tenant_name = tenant.name.clone();
+            }
+        }
+
+        if self.application_id.is_none() && self.application_name.is_none() {
+            if let Some(application) = manifest.application.as_ref() {
+                self.application_id = application.id.clone();
+                self.application_name = application.name.clone();
+                self.bundle_id =
+                    self.bundle_id.take().or(application.bundle_id.clone());
+            }
+        }
+
+        let mut input_answers = manifest.input_answers();
+        input_answers.append(&mut self.input_answers);
+        self.input_answers = input_answers;
+
+        self.template_name = self.template_name.take().or(manifest.template);
+        self.template_source =
+            self.template_source.take().or(manifest.template_source);
+        self.project_path = self.project_path.take().or(manifest.project_path);
+        self.marketing_version =
+            self.marketing_version.take().or(manifest.marketing_version);
+        self.build_number = self.build_number.take().or(manifest.build_number);
+        self.deployment_target =
+            self.deployment_target.take().or(manifest.deployment_target);
+
+        if let Some(code_signing) = manifest.code_signing {
+            self.code_signing.apply_manifest(code_signing)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl CodeSigningOptions {
+    fn apply_manifest(
+        &mut self,
+        manifest: ManifestCodeSigning,
+    ) -> Result<(), ParraCliError> {
+        self.team_id = self.team_id.take().or(manifest.team_id);
+        self.style = self
+            .style
+            .take()
+            .or(parse_code_sign_style(manifest.style.as_deref())?);
+        self.debug.apply_manifest(manifest.debug)?;
+        self.release.apply_manifest(manifest.release)?;
+
+        Ok(())
+    }
+
+    /// Checks everything about the settings that doesn't depend on the
+    /// application, so that signing that can't work is reported before
+    /// anything is created. See also `validate_team_id`.
+    fn validate(&self) -> Result<(), ParraCliError> {
+        if let Some(team_id) = &self.team_id {
+            let re = Regex::new(r"^[A-Z0-9]{10}$").unwrap();
+
+            if !re.is_match(team_id) {
+                return Err(ParraCliError::InvalidInput(format!(
+                    "The team ID \"{}\" isn't valid. Team IDs are 10 uppercase letters and digits.",
+                    team_id
+                )));
+            }
+        }
+
+        for (name, configuration) in
+            [("Debug", &self.debug), ("Release", &self.release)]
+        {
+            if let Some(identity) = &configuration.identity {
+                if identity.trim().is_empty() {
+                    return Err(ParraCliError::InvalidInput(format!(
+                        "The signing identity for the {} configuration can't be empty.",
+                        name
+                    )));
+                }
+            }
+
+            let has_profile = configuration
+                .profile_specifier
+                .as_ref()
+                .is_some_and(|profile| !profile.trim().is_empty());
+
+            match configuration.style.or(self.style) {
+                Some(CodeSignStyle::Manual) => {
+                    if !has_profile {
+                        return Err(ParraCliError::InvalidInput(format!(
+                            "Manual signing for the {} configuration requires a provisioning profile.",
+                            name
+                        )));
+                    }
+                }
+                Some(CodeSignStyle::Automatic) | None => {
+                    if has_profile {
+                        return Err(ParraCliError::InvalidInput(format!(
+                            "A provisioning profile was given for the {} configuration, which uses automatic signing. Use manual signing to choose a profile.",
+                            name
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that there is a team when manual signing is used. `team_id` is
+    /// the team that will be used, which may come from the application.
+    fn validate_team_id(&self, team_id: &str) -> Result<(), ParraCliError> {
+        let uses_manual_signing =
+            [&self.debug, &self.release].iter().any(|configuration| {
+                configuration.style.or(self.style)
+                    == Some(CodeSignStyle::Manual)
+            });
+
+        if uses_manual_signing && team_id.is_empty() {
+            return Err(ParraCliError::InvalidInput(
+                "Manual signing requires a team ID. Pass --team-id or set one for the application in the Parra dashboard.".to_string(),
+            ));
+        }
+
+        return Ok(());
+    }
+
+    /// The settings rendered into the project, falling back on automatic
+    /// signing with the standard identities.
+    fn configs(&self) -> CodeSigningConfigs {
+        return CodeSigningConfigs {
+            debug: self.debug.config(&self.style, "Apple Development"),
+            release: self.release.config(&self.style, "Apple Distribution"),
+        };
+    }
+}
+
+impl SigningConfigurationOptions {
+    fn apply_manifest(
+        &mut self,
+        manifest: ManifestSigningConfiguration,
+    ) -> Result<(), ParraCliError> {
+        self.identity = self.identity.take().or(manifest.identity);
+        self.style = self
+            .style
+            .take()
+            .or(parse_code_sign_style(manifest.style.as_deref())?);
+        self.profile_specifier =
+            self.profile_specifier.take().or(manifest.profile_specifier);
+
+        Ok(())
+    }
+
+    fn config(
+        &self,
+        shared_style: &Option<CodeSignStyle>,
+        default_identity: &str,
+    ) -> CodeSigningConfig {
+        return CodeSigningConfig {
+            identity: self
+                .identity
+                .clone()
+                .unwrap_or(default_identity.to_owned()),
+            required: "YES".to_owned(),
+            allowed: "YES".to_owned(),
+            style: self
+                .style
+                .or(*shared_style)
+                .unwrap_or(CodeSignStyle::Automatic)
+                .to_string(),
+            profile_specifier: self
+                .profile_specifier
+                .clone()
+                .unwrap_or_default(),
+        };
+    }
+}
+
+fn parse_code_sign_style(
+    style: Option<&str>,
+) -> Result<Option<CodeSignStyle>, ParraCliError> {
+    let Some(style) = style else {
+        return Ok(None);
+    };
+
+    CodeSignStyle::from_str(style, true).map(Some).map_err(|_| {
+        ParraCliError::InvalidInput(format!(
+            "Unknown code signing style \"{}\". Use Automatic or Manual.",
+            style
+        ))
+    })
+}
+
 impl Display for TenantResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} (Tenant ID: {})", self.name, self.id)
@@ -71,25 +400,36 @@ impl Display for SemanticVersion {
 pub async fn execute_sample_bootstrap(
     project_path: Option<String>,
     use_local_packages: bool,
-) -> Result<(), Box<dyn Error>> {
+    code_signing: CodeSigningOptions,
+    template_source: Option<String>,
+) -> Result<(), ParraCliError> {
     println!("Preparing to generate Parra Sample project. Will link packages locally: {}", use_local_packages);
 
-    let template_name = "default";
-    let template_dir = get_template_path(&template_name)?;
-    let template_app_dir = template_dir.join("App/");
-    // Sample app generation will always use the local template. Even in CI, the template
-    // is accessible and should have already been updated for any necessary SDK changes by
-    // this point.
-    let template = get_local_template(&template_dir, use_local_packages)?;
+    // Sample app generation uses the local template unless another source is
+    // given. Even in CI, the template is accessible and should have already
+    // been updated for any necessary SDK changes by this point.
+    let template_source = match template_source {
+        Some(template_source) => TemplateSource::parse(&template_source)?,
+        None => TemplateSource::Local(get_templates_dir_path()?),
+    };
+
+    let fetched_templates =
+        template_source::fetch_templates(&template_source).await?;
+    let template = fetched_templates.select(None)?;
+    let template_name = template.name.clone();
+
+    let resolved_template =
+        ResolvedTemplate::load(template, use_local_packages)?;
+
+    let template_app_dir = resolved_template.template_dir.join("App/");
 
     let project_dir: PathBuf = if let Some(project_path) = project_path {
-        normalized_project_path(Some(project_path), "ParraSample")?
+        normalized_project_path(Some(project_path), "ParraSample", false)?
     } else {
         get_sample_path()?
     };
 
-    let project_config =
-        read_template_config(&template_dir, use_local_packages)?;
+    let project_config = resolved_template.config;
 
     println!("Will generate sample in: {}", project_dir.display());
 
@@ -106,6 +446,14 @@ pub async fn execute_sample_bootstrap(
         Err(_) => "1".to_owned(),
     };
 
+    let team_id = code_signing
+        .team_id
+        .clone()
+        .unwrap_or("6D44Q764PG".to_owned());
+
+    code_signing.validate()?;
+    code_signing.validate_team_id(&team_id)?;
+
     let context: ProjectContext = ProjectContext {
         app: AppContextInfo {
             id: demo_app_id.to_owned(),
@@ -119,23 +467,8 @@ pub async fn execute_sample_bootstrap(
             },
             bundle_id: "com.parra.parra-ios-client".to_owned(),
             deployment_target: "17.0".to_owned(),
-            code_sign: CodeSigningConfigs {
-                debug: CodeSigningConfig {
-                    identity: "Apple Development".to_owned(),
-                    required: "YES".to_owned(),
-                    allowed: "YES".to_owned(),
-                    style: "Automatic".to_owned(),
-                    profile_specifier: "".to_owned(),
-                },
-                release: CodeSigningConfig {
-                    identity: "Apple Distribution".to_owned(),
-                    required: "YES".to_owned(),
-                    allowed: "YES".to_owned(),
-                    style: "Automatic".to_owned(),
-                    profile_specifier: "".to_owned(),
-                },
-            },
-            team_id: "6D44Q764PG".to_owned(),
+            code_sign: code_signing.configs(),
+            team_id,
             entitlements: get_entitlement_schemes(vec![
                 TenantDomain {
                     host: "parra-demo.com".to_owned(),
@@ -167,52 +500,215 @@ pub async fn execute_sample_bootstrap(
     let xcode_project_path =
         project_generator::generator::generate_xcode_project(
             &project_dir,
-            &template_dir,
+            &resolved_template.templates_dir,
             &template_app_dir,
-            &template,
+            &resolved_template.project_spec,
             &context,
-            false,
+            ExistingProjectPolicy::Overwrite,
+            true,
+            &BTreeMap::new(),
         )
         .await?;
 
     println!(
         "Parra project generated at {}!",
-        xcode_project_path.to_str().unwrap()
+        xcode_project_path.display()
     );
 
     Ok(())
 }
 
 pub async fn execute_bootstrap(
-    application_id: Option<String>,
-    tenant_id: Option<String>,
-    project_path: Option<String>,
-    template_name: String,
-) -> Result<(), Box<dyn Error>> {
+    mut options: BootstrapOptions,
+) -> Result<(), ParraCliError> {
     let _ = api::report_event("cli_bootstrap_started", None);
 
-    let tenant = get_tenant(tenant_id).await?;
-    let tenant_copy = tenant.clone();
+    // Answers from flags take precedence over the manifest's, so they have to
+    // be collected first.
+    options
+        .input_answers
+        .extend(project_generator::inputs::collect_answers(
+            &options.inputs,
+            options.inputs_path.as_ref(),
+        )?);
+
+    options.apply_manifest()?;
+    options.code_signing.validate()?;
+
+    let (template_source, use_local_packages) =
+        configured_template_source(options.template_source.as_deref())?;
+
+    let interactive = !options.non_interactive;
+
+    // Templates are loaded up front, so that an unknown template is reported
+    // before anything is created. Without --template, interactive bootstraps
+    // ask for one once the application is known.
+    let fetched_templates =
+        template_source::fetch_templates(&template_source).await?;
+    let requested_template = match options.template_name.as_deref() {
+        Some(template_name) => {
+            Some(fetched_templates.select(Some(template_name))?)
+        }
+        None if interactive => None,
+        None => Some(fetched_templates.select(None)?),
+    };
+
+    // Answers are checked before anything is created or prompted for. Without
+    // a template yet, that waits until one is picked.
+    if let Some(template) = requested_template {
+        project_generator::inputs::validate_answers(
+            &template.config,
+            interactive,
+            &options.input_answers,
+        )?;
+    }
+
+    let marketing_version = options
+        .marketing_version
+        .take()
+        .unwrap_or("1.0.0".to_owned());
+    let build_number = options.build_number.take().unwrap_or("1".to_owned());
+
+    validate_version_number("marketing version", &marketing_version)?;
+    validate_version_number("build number", &build_number)?;
+
+    let existing_project_policy = if options.overwrite {
+        ExistingProjectPolicy::Overwrite
+    } else if interactive {
+        ExistingProjectPolicy::Prompt
+    } else {
+        ExistingProjectPolicy::Fail
+    };
+
+    // Problems that would otherwise only surface once the project is being
+    // generated are checked before anything is created in Parra. The project
+    // path is known up front when an application is named, since it's derived
+    // from the name. Dependencies are only checked this early when missing
+    // ones would fail the bootstrap, rather than be installed or skipped.
+    if !options.dry_run {
+        if let Some(application_name) = &options.application_name {
+            if existing_project_policy == ExistingProjectPolicy::Fail {
+                let expanded_path = normalized_project_path(
+                    options.project_path.clone(),
+                    &safe_app_name(application_name.trim()),
+                    interactive,
+                )?;
+
+                check_existing_project(
+                    &expanded_path,
+                    existing_project_policy,
+                )?;
+            }
+        }
+
+        let missing_dependencies_fail = match options.dependency_policy {
+            Some(DependencyPolicy::Fail) => true,
+            Some(DependencyPolicy::Install) | Some(DependencyPolicy::Skip) => {
+                false
+            }
+            None => !interactive,
+        };
+
+        if missing_dependencies_fail {
+            check_dependency_policy(
+                &missing_dependencies()?,
+                options.dependency_policy,
+                interactive,
+            )?;
+        }
+    }
+
+    // The device authorization flow asks the user to confirm in the browser,
+    // so a login has to exist up front.
+    if !interactive && auth::get_existing_credential().await?.is_none() {
+        return Err(ParraCliError::Auth(format!(
+            "Not logged in. Run `parra login` or set {} before bootstrapping non-interactively.",
+            auth::TOKEN_ENV_VAR
+        )));
+    }
+
+    // Fall back on the defaults stored for the selected profile. The default
+    // application only applies when it belongs to the tenant being used, and
+    // neither applies when a name was given to look up or create instead.
+    let profile = profiles::current_profile()?;
+    let tenant_id = options.tenant_id;
+    let application_id = if options.application_name.is_none()
+        && (tenant_id.is_none() || tenant_id == profile.tenant_id)
+    {
+        options.application_id.or(profile.application_id)
+    } else {
+        options.application_id
+    };
+    let tenant_id = if options.tenant_name.is_none() {
+        tenant_id.or(profile.tenant_id)
+    } else {
+        tenant_id
+    };
+
+    let tenant = get_tenant(
+        tenant_id,
+        options.tenant_name,
+        interactive,
+        options.dry_run,
+    )
+    .await?;
 
     let _ = api::report_event(
         "cli_bootstrap_tenant_selected",
         Some(HashMap::from([("tenant_id", tenant.id.as_str())])),
     );
 
-    let mut application = get_application(application_id, &tenant).await?;
-    let application_copy = application.clone();
+    let application = get_application(
+        application_id,
+        options.application_name,
+        options.bundle_id,
+        &tenant,
+        interactive,
+        options.dry_run,
+    )
+    .await?;
 
     let _ = api::report_event(
         "cli_bootstrap_application_selected",
         Some(HashMap::from([("application_id", application.id.as_str())])),
     );
 
-    let server_driven_template = api::post_bootstrap_request(
-        &tenant.id,
-        &application.id,
-        Some(template_name.clone()),
-    )
-    .await?;
+    let tenant = tenant.into_inner();
+    let application = application.into_inner();
+    let tenant_copy = tenant.clone();
+    let application_copy = application.clone();
+
+    let template = match requested_template {
+        Some(template) => template,
+        None => {
+            let template = prompt_for_template(&fetched_templates)?;
+
+            project_generator::inputs::validate_answers(
+                &template.config,
+                interactive,
+                &options.input_answers,
+            )?;
+
+            template
+        }
+    };
+    let template_name = template.name.clone();
+
+    // The bootstrap request isn't sent during a dry run, since the application
+    // may not exist and the server may record it. The template's defaults are
+    // used instead of the application's server side template data.
+    let server_driven_template = if options.dry_run {
+        None
+    } else {
+        Some(
+            api::post_bootstrap_request(
+                &tenant.id,
+                &application.id,
+                Some(template_name.clone()),
+            )
+            .await?,
+        )
+    };
 
     let _ = api::report_event("cli_bootstrap_template_selected", None);
 
@@ -229,38 +725,31 @@ pub async fn execute_bootstrap(
         .collect::<Vec<&str>>()
         .join(" ");
 
-    // If the app name ends with "App", remove it.
-    if application.name.to_lowercase().ends_with("app") {
-        application.name = application
-            .name
-            .trim_end_matches("app")
-            .trim_end_matches("App")
-            .trim()
-            .to_string();
-    }
-
-    let safe_app_name = Regex::new(r"[^a-zA-Z0-9\s-]")
-        .unwrap()
-        .replace_all(&application.name, "");
+    let safe_app_name = safe_app_name(&application.name);
 
-    let expanded_path = normalized_project_path(project_path, &safe_app_name)?;
+    let expanded_path = normalized_project_path(
+        options.project_path,
+        &safe_app_name,
+        interactive,
+    )?;
 
-    let (template, template_dir): (String, PathBuf) = if cfg!(debug_assertions)
-    {
-        println!("Running in DEBUG mode. Using local templates!!!");
-        let template_dir = get_template_path(&template_name)?;
-        let template = get_local_template(&template_dir, true)?;
+    if !options.dry_run {
+        check_existing_project(&expanded_path, existing_project_policy)?;
+    }
 
-        (template, template_dir)
-    } else {
-        get_remote_template(&template_name).await?
-    };
+    let resolved_template =
+        ResolvedTemplate::load(template, use_local_packages)?;
 
     let _ = api::report_event("cli_bootstrap_template_cloned", None);
 
-    let template_app_dir = template_dir.join("App");
+    let template_app_dir = resolved_template.template_dir.join("App");
 
-    let ios_config = application.ios.unwrap();
+    let ios_config = application.ios.ok_or_else(|| {
+        ParraCliError::InvalidInput(format!(
+            "Application {} doesn't have an iOS configuration",
+            application.id
+        ))
+    })?;
 
     // If the user didn't include the word app, anywhere in the app name, add it to the end
     // of the name used for the main App struct. Creating a slug, then upper camel converting
@@ -271,17 +760,29 @@ pub async fn execute_bootstrap(
         slugify!(&safe_app_name).to_case(Case::UpperCamel) + "App"
     };
 
-    let team_id = ios_config.team_id.unwrap_or("".to_owned());
-    let project_config =
-        read_template_config(&template_dir, cfg!(debug_assertions))?;
+    let team_id = options
+        .code_signing
+        .team_id
+        .clone()
+        .or(ios_config.team_id)
+        .unwrap_or("".to_owned());
+
+    options.code_signing.validate_team_id(&team_id)?;
+    let project_config = resolved_template.config;
 
     let _ = api::report_event("cli_bootstrap_template_parsed", None);
 
+    let deployment_target = resolve_deployment_target(
+        options.deployment_target,
+        &project_config,
+        &template_name,
+    )?;
+
     let context: ProjectContext = ProjectContext {
         app: AppContextInfo {
             id: application.id,
-            build_number: "1".to_owned(),
-            marketing_version: "1.0.0".to_owned(),
+            build_number,
+            marketing_version,
             name: AppNameInfo {
                 raw: safe_app_name.to_string(),
                 // Slugify correctly handles cases like "My iOS App" -> "my-ios-app" instead of "my-i-os-app"
@@ -290,23 +791,8 @@ pub async fn execute_bootstrap(
                 display_name: display_name,
             },
             bundle_id: ios_config.bundle_id,
-            deployment_target: "17.0".to_owned(),
-            code_sign: CodeSigningConfigs {
-                debug: CodeSigningConfig {
-                    identity: "Apple Development".to_owned(),
-                    required: "YES".to_owned(),
-                    allowed: "YES".to_owned(),
-                    style: "Automatic".to_owned(),
-                    profile_specifier: "".to_owned(),
-                },
-                release: CodeSigningConfig {
-                    identity: "Apple Distribution".to_owned(),
-                    required: "YES".to_owned(),
-                    allowed: "YES".to_owned(),
-                    style: "Automatic".to_owned(),
-                    profile_specifier: "".to_owned(),
-                },
-            },
+            deployment_target,
+            code_sign: options.code_signing.configs(),
             team_id: team_id.to_owned(),
             entitlements: get_entitlement_schemes(tenant.domains),
             icon: application.icon.or(tenant.logo),
@@ -321,12 +807,24 @@ pub async fn execute_bootstrap(
         config: project_config,
         template: TemplateInfo::with_bootstrap_response(
             &template_name,
-            Some(&server_driven_template),
+            server_driven_template.as_ref(),
         ),
     };
 
-    let mut templates_dir = template_dir.clone();
-    templates_dir.pop();
+    let templates_dir = resolved_template.templates_dir;
+    let template = resolved_template.project_spec;
+
+    if options.dry_run {
+        return preview_bootstrap(
+            &expanded_path,
+            &templates_dir,
+            &template_app_dir,
+            &template,
+            &context,
+            interactive,
+            &options.input_answers,
+        );
+    }
 
     api::complete_bootstrap(&tenant_copy.id, &application_copy.id).await?;
 
@@ -336,196 +834,236 @@ pub async fn execute_bootstrap(
         &template_app_dir,
         &template,
         &context,
-        true,
+        existing_project_policy,
+        interactive,
+        &options.input_answers,
     )
     .await?;
 
     let xcode_target_dir = &xcode_project;
 
-    println!(
-        "Parra project generated at {}!",
-        expanded_path.to_str().unwrap()
-    );
+    println!("Parra project generated at {}!", expanded_path.display());
 
-    let missing = missing_dependencies();
+    let missing = missing_dependencies()?;
     // If all dependencies are met, open the project. Otherwise, prompt the user to install them,
     // and then open the project on completion.
-    if missing.is_empty() {
-        open_project(xcode_target_dir, &context).await?;
+    let dependencies_installed = if missing.is_empty() {
+        true
     } else {
-        dependencies(missing).await?;
+        dependencies(missing, options.dependency_policy, interactive).await?
+    };
 
-        open_project(xcode_target_dir, &context).await?;
-    }
+    // Launching Xcode is skipped when scripting, and when it may be missing.
+    let launch_xcode = interactive && dependencies_installed;
+
+    open_project(xcode_target_dir, &context, launch_xcode).await?;
 
     let _ = api::report_event("cli_bootstrap_succeeded", None);
 
     Ok(())
 }
 
-fn read_template_config(
-    template_dir: &PathBuf,
-    use_local_packages: bool,
-) -> Result<TemplateConfig, Box<dyn Error>> {
-    let project_template_path = template_dir.join("config.json");
-
-    let config: String = if use_local_packages {
-        read_template_file_following_links(project_template_path)?
-    } else {
-        read_to_string(project_template_path)?
-    };
-
-    return Ok(serde_json::from_str::<TemplateConfig>(&config)?);
-}
+/// Renders the project to a temporary directory and prints what would have
+/// been generated at `project_path`, without touching it. The temporary
+/// directory is removed afterwards.
+fn preview_bootstrap(
+    project_path: &PathBuf,
+    templates_dir: &PathBuf,
+    template_app_dir: &PathBuf,
+    template: &str,
+    context: &ProjectContext,
+    interactive: bool,
+    input_answers: &BTreeMap<String, String>,
+) -> Result<(), ParraCliError> {
+    let temp_dir = TempDir::create_in(
+        &env::temp_dir(),
+        &format!("parra-dry-run-{}", context.app.name.kebab),
+    )?;
+    let preview_dir = temp_dir.path().to_path_buf();
+
+    let globals = project_generator::generator::render_project(
+        &preview_dir,
+        templates_dir,
+        template_app_dir,
+        template,
+        context,
+        interactive,
+        input_answers,
+    )?;
+
+    println!("Resolved context:");
+    println!("{}", serde_json::to_string_pretty(&globals)?);
+    println!();
+    println!("Rendered files:");
+
+    for entry in WalkDir::new(&preview_dir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|error| ParraCliError::Filesystem {
+            path: Some(preview_dir.clone()),
+            source: error.into(),
+        })?;
+
+        let indent = "  ".repeat(entry.depth() - 1);
+        let suffix = if entry.file_type().is_dir() { "/" } else { "" };
+
+        println!(
+            "{}{}{}",
+            indent,
+            entry.file_name().to_string_lossy(),
+            suffix
+        );
+    }
 
-fn normalized_project_path(
-    project_path: Option<String>,
-    app_name: &str,
-) -> Result<PathBuf, Box<dyn Error>> {
-    // Slugify correctly handles cases like "My iOS App" -> "my-ios-app" instead of "my-i-os-app"
-    let kebab_name = slugify!(app_name);
-    let relative_path = get_project_path(project_path, &kebab_name);
+    println!();
 
-    let mut project_path = PathBuf::from_str(&relative_path)?;
-    if !project_path.ends_with(&kebab_name) {
-        project_path.push(&kebab_name);
+    if project_path.exists() {
+        println!(
+            "{} already exists and would be replaced.",
+            project_path.display()
+        );
     }
 
-    let expanded_path = expand_tilde(&project_path).unwrap();
+    println!(
+        "Dry run complete. Nothing was written to {}.",
+        project_path.display()
+    );
 
-    return Ok(expanded_path);
+    return Ok(());
 }
 
-fn resolve_template_symlinks(
-    templates_dir: &PathBuf,
-    current_dir: &PathBuf,
-) -> io::Result<()> {
-    for entry in fs::read_dir(current_dir)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-
-        if ty.is_dir() {
-            // recurse
-            resolve_template_symlinks(templates_dir, &entry.path())?;
-        } else if entry.path().is_symlink() {
-            let mut target = entry.path().clone();
-
-            while target.is_symlink() {
-                let followed = fs::read_link(target)?;
-
-                target = templates_dir.join(followed);
-            }
-
-            let full_target = templates_dir.join(target);
+/// Apple's marketing versions and build numbers are one to three period
+/// separated non-negative integers.
+fn validate_version_number(
+    name: &str,
+    version: &str,
+) -> Result<(), ParraCliError> {
+    let re = Regex::new(r"^\d+(\.\d+){0,2}$").unwrap();
 
-            // replace the symlink with a copy of the linked file
-            fs::remove_file(entry.path())?;
-            fs::copy(full_target, entry.path())?;
-        } else {
-            // do nothing
-        }
+    if re.is_match(version) {
+        return Ok(());
     }
 
-    Ok(())
+    return Err(ParraCliError::InvalidInput(format!(
+        "The {} \"{}\" isn't valid. Use one to three period separated numbers, e.g. 1.2.0.",
+        name, version
+    )));
 }
 
-fn read_template_file_following_links(
-    path: PathBuf,
-) -> Result<String, Box<dyn Error>> {
-    let mut project_template_path = path.clone();
-    let templates_dir: PathBuf = get_templates_dir_path()?;
+/// Parses a deployment target like "17.0" into its numeric components.
+fn parse_deployment_target(target: &str) -> Option<Vec<u64>> {
+    let re = Regex::new(r"^\d+(\.\d+){0,2}$").unwrap();
 
-    if project_template_path.is_symlink() {
-        let followed = fs::read_link(project_template_path)?;
-        project_template_path = templates_dir.join(followed);
+    if !re.is_match(target) {
+        return None;
     }
 
-    let template = read_to_string(project_template_path)?;
+    let mut components: Vec<u64> = target
+        .split('.')
+        .map(|component| component.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
 
-    Ok(template)
-}
+    // So that "17" and "17.0" compare as equal.
+    components.resize(3, 0);
 
-fn get_local_template(
-    template_dir: &PathBuf,
-    use_local_packages: bool,
-) -> Result<String, Box<dyn Error>> {
-    let project_template_path = template_dir.join("project.yml");
+    return Some(components);
+}
 
-    let template: String = if use_local_packages {
-        read_template_file_following_links(project_template_path)?
-    } else {
-        read_to_string(project_template_path)?
+/// The requested deployment target, or the template's minimum when there is
+/// none. Fails if the requested target is below what the template supports.
+pub fn resolve_deployment_target(
+    requested: Option<String>,
+    template_config: &TemplateConfig,
+    template_name: &str,
+) -> Result<String, ParraCliError> {
+    let minimum = template_config
+        .min_deployment_target
+        .clone()
+        .unwrap_or(DEFAULT_DEPLOYMENT_TARGET.to_owned());
+
+    let minimum_components =
+        parse_deployment_target(&minimum).ok_or_else(|| {
+            ParraCliError::InvalidInput(format!(
+                "The {} template declares an invalid minimum deployment target \"{}\".",
+                template_name, minimum
+            ))
+        })?;
+
+    let Some(requested) = requested else {
+        return Ok(minimum);
     };
 
-    let package_template: String = if use_local_packages {
-        println!("Using local packages for project generation.");
-        read_template_file_following_links(
-            template_dir.join("package_local.yml"),
-        )?
-    } else {
-        println!("Using remote packages for project generation.");
-        read_to_string(template_dir.join("package_remote.yml"))?
-    };
+    let requested_components =
+        parse_deployment_target(&requested).ok_or_else(|| {
+            ParraCliError::InvalidInput(format!(
+                "The deployment target \"{}\" isn't valid. Use an iOS version like 17.0.",
+                requested
+            ))
+        })?;
+
+    if requested_components < minimum_components {
+        return Err(ParraCliError::InvalidInput(format!(
+            "The {} template requires a deployment target of iOS {} or later, but {} was requested.",
+            template_name, minimum, requested
+        )));
+    }
 
-    Ok(format!("{}\n{}", template, package_template))
+    return Ok(requested);
 }
 
-async fn get_remote_template(
-    template_name: &str,
-) -> Result<(String, PathBuf), Box<dyn Error>> {
-    let version = built_info::PKG_VERSION;
+fn normalized_project_path(
+    project_path: Option<String>,
+    app_name: &str,
+    interactive: bool,
+) -> Result<PathBuf, ParraCliError> {
+    // Slugify correctly handles cases like "My iOS App" -> "my-ios-app" instead of "my-i-os-app"
+    let kebab_name = slugify!(app_name);
+    let relative_path =
+        get_project_path(project_path, &kebab_name, interactive)?;
 
-    let tmp_dir_output = Command::new("mktemp")
-        .arg("-d")
-        .output()
-        .expect("Error creating temporary directory");
-    let tmp_dir = String::from_utf8(tmp_dir_output.stdout)?.trim().to_string();
-
-    Command::new("git")
-        .arg("clone")
-        .arg("--no-checkout")
-        .arg("--depth=1")
-        .arg("--filter=tree:0")
-        .arg("https://github.com/Parra-Inc/parra-mobile-sdks")
-        .arg(tmp_dir.clone())
-        .output()
-        .expect("Failed to clone template repo");
-
-    Command::new("git")
-        .arg("sparse-checkout")
-        .arg("set")
-        .arg("--no-cone")
-        .arg("templates")
-        .current_dir(tmp_dir.clone())
-        .output()
-        .expect("Failed to perform template sparse checkout");
-
-    Command::new("git")
-        .arg("fetch")
-        .arg("origin")
-        .arg("tag")
-        .arg(version)
-        // --no-tags ensures no additional tags are pulled.
-        .arg("--no-tags")
-        .current_dir(tmp_dir.clone())
-        .output()
-        .expect("Failed to perform template tag fetch");
+    let mut project_path = PathBuf::from(&relative_path);
+    if !project_path.ends_with(&kebab_name) {
+        project_path.push(&kebab_name);
+    }
 
-    Command::new("git")
-        .arg("checkout")
-        .arg(format!("tags/{}", version))
-        .current_dir(tmp_dir.clone())
-        .output()
-        .expect("Failed to perform checkout at tag");
+    let expanded_path = expand_tilde(&project_path).ok_or_else(|| {
+        ParraCliError::InvalidInput(format!(
+            "Couldn't expand {} because the home directory is unknown",
+            project_path.display()
+        ))
+    })?;
+
+    return Ok(expanded_path);
+}
 
-    let templates_dir = Path::new(&tmp_dir).join("templates");
-    let template_dir = templates_dir.join(template_name);
+/// The application's name as it's used for the project, without a trailing
+/// "App" and with only letters, digits, whitespace and hyphens.
+fn safe_app_name(name: &str) -> String {
+    // If the app name ends with "App", remove it.
+    let name = if name.to_lowercase().ends_with("app") {
+        name.trim_end_matches("app").trim_end_matches("App").trim()
+    } else {
+        name
+    };
 
-    resolve_template_symlinks(&templates_dir, &template_dir)?;
+    return Regex::new(r"[^a-zA-Z0-9\s-]")
+        .unwrap()
+        .replace_all(name, "")
+        .to_string();
+}
 
-    let template = get_local_template(&template_dir, false)?;
+/// Fails if the project directory exists and the policy doesn't allow it to
+/// be replaced. Generation checks again, this only fails sooner.
+fn check_existing_project(
+    project_dir: &Path,
+    policy: ExistingProjectPolicy,
+) -> Result<(), ParraCliError> {
+    if policy == ExistingProjectPolicy::Fail && project_dir.exists() {
+        return Err(ParraCliError::InvalidInput(format!(
+            "Project directory {} already exists. Pass --overwrite to replace it.",
+            project_dir.display()
+        )));
+    }
 
-    return Ok((template, template_dir));
+    return Ok(());
 }
 
 fn get_entitlement_schemes(
@@ -565,18 +1103,71 @@ fn get_entitlement_schemes(
     };
 }
 
-fn missing_dependencies() -> Vec<DerivedDependency> {
+fn missing_dependencies() -> Result<Vec<DerivedDependency>, ParraCliError> {
     dependencies::check_for_missing_dependencies(
         MIN_XCODE_VERSION,
         DESIRED_IOS_RUNTIME_VERSION,
     )
 }
 
+fn missing_dependencies_message() -> String {
+    return format!(
+        "Xcode version {} or later and its iOS {} runtime are required to build your new project.",
+        MIN_XCODE_VERSION, DESIRED_IOS_RUNTIME_VERSION
+    );
+}
+
+/// Fails if dependencies are missing and the policy says to fail, or there is
+/// no policy and the user can't be asked.
+fn check_dependency_policy(
+    missing: &[DerivedDependency],
+    policy: Option<DependencyPolicy>,
+    interactive: bool,
+) -> Result<(), ParraCliError> {
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    match policy {
+        Some(DependencyPolicy::Fail) => {
+            return Err(ParraCliError::InvalidInput(
+                missing_dependencies_message(),
+            ));
+        }
+        None if !interactive => {
+            return Err(ParraCliError::InvalidInput(format!(
+                "{} Pass --dependencies install or --dependencies skip to continue without prompting.",
+                missing_dependencies_message()
+            )));
+        }
+        Some(DependencyPolicy::Install)
+        | Some(DependencyPolicy::Skip)
+        | None => {}
+    }
+
+    return Ok(());
+}
+
+/// Installs missing dependencies according to the policy, asking the user when
+/// there is none. Returns whether they were installed.
 async fn dependencies(
     missing: Vec<DerivedDependency>,
-) -> Result<(), Box<dyn Error>> {
+    policy: Option<DependencyPolicy>,
+    interactive: bool,
+) -> Result<bool, ParraCliError> {
     if missing.is_empty() {
-        return Ok(());
+        return Ok(true);
+    }
+
+    check_dependency_policy(&missing, policy, interactive)?;
+
+    if policy == Some(DependencyPolicy::Skip) {
+        println!(
+            "{} Skipping their installation.",
+            missing_dependencies_message()
+        );
+
+        return Ok(false);
     }
 
     let _ = api::report_event(
@@ -592,30 +1183,34 @@ async fn dependencies(
         "\nWe need to install a few dependencies first. Proceed?"
     };
 
-    let confirmed_install =
-        Confirm::new(confirm_message).with_default(true).prompt()?;
+    let confirmed_install = policy == Some(DependencyPolicy::Install)
+        || Confirm::new(confirm_message).with_default(true).prompt()?;
 
     // Trim the input and check if it's an affirmative response
     if confirmed_install {
         dependencies::install_missing_dependencies(
             DESIRED_XCODE_VERSION,
             DESIRED_IOS_RUNTIME_VERSION,
-        );
+        )?;
     } else {
-        println!("Please install Xcode version {} or later before opening your new project.", MIN_XCODE_VERSION);
-
-        exit(1)
+        return Err(ParraCliError::UserCancelled(format!(
+            "Please install Xcode version {} or later before opening your new project.",
+            MIN_XCODE_VERSION
+        )));
     }
 
     let _ =
         api::report_event("cli_bootstrap_project_dependencies_installed", None);
 
-    Ok(())
+    Ok(true)
 }
 
 async fn get_tenant(
     tenant_arg: Option<String>,
-) -> Result<TenantResponse, Box<dyn Error>> {
+    tenant_name: Option<String>,
+    interactive: bool,
+    dry_run: bool,
+) -> Result<Resolved<TenantResponse>, ParraCliError> {
     // The user provided a tenant ID directly.
     if let Some(tenant_id) = tenant_arg {
         let _ = api::report_event(
@@ -623,13 +1218,31 @@ async fn get_tenant(
             Some(HashMap::from([("tenant_id", tenant_id.as_str())])),
         );
 
-        return api::get_tenant(&tenant_id).await;
+        return api::get_tenant(&tenant_id).await.map(Resolved::Existing);
     }
 
     let tenants = api::get_tenants().await?;
 
+    if let Some(tenant_name) = tenant_name {
+        return find_or_create_tenant(tenants, &tenant_name, dry_run).await;
+    }
+
+    if !interactive {
+        if tenants.len() == 1 {
+            return Ok(Resolved::Existing(tenants[0].clone()));
+        }
+
+        let message = if tenants.is_empty() {
+            "No workspaces exist yet. Pass --tenant-name to create one."
+        } else {
+            "Multiple workspaces exist. Pass --tenant-id or --tenant-name to choose one."
+        };
+
+        return Err(ParraCliError::InvalidInput(message.to_string()));
+    }
+
     if tenants.is_empty() {
-        return create_new_tenant(false).await;
+        return create_new_tenant(false, dry_run).await;
     }
 
     let _ = api::report_event("cli_bootstrap_tenant_existed", None);
@@ -645,9 +1258,9 @@ async fn get_tenant(
         let use_only = Confirm::new(&prompt).with_default(true).prompt()?;
 
         if use_only {
-            return Ok(tenant);
+            return Ok(Resolved::Existing(tenant));
         } else {
-            return create_new_tenant(true).await;
+            return create_new_tenant(true, dry_run).await;
         }
     }
 
@@ -665,30 +1278,133 @@ async fn get_tenant(
                 .with_page_size(10)
                 .prompt();
 
-        return Ok(selected_tenant?);
+        return Ok(Resolved::Existing(selected_tenant?));
     } else {
-        return create_new_tenant(true).await;
+        return create_new_tenant(true, dry_run).await;
+    }
+}
+
+/// A template in the picker, described by its config.
+struct TemplateOption<'a> {
+    template: &'a TemplateSummary,
+}
+
+impl Display for TemplateOption<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.template.display_name())?;
+
+        if let Some(description) = &self.template.config.description {
+            write!(f, " - {}", description)?;
+        }
+
+        let tabs = &self.template.config.required_tabs;
+
+        if !tabs.is_empty() {
+            write!(f, " [{}]", tabs.join(", "))?;
+        }
+
+        Ok(())
     }
 }
 
+/// Asks which template to use, starting on the default. Only the templates'
+/// own configs are shown, so nothing is sent to Parra until one is chosen.
+fn prompt_for_template(
+    fetched_templates: &FetchedTemplates,
+) -> Result<&TemplateSummary, ParraCliError> {
+    if let [template] = fetched_templates.templates.as_slice() {
+        return Ok(template);
+    }
+
+    let options: Vec<TemplateOption> = fetched_templates
+        .templates
+        .iter()
+        .map(|template| TemplateOption { template })
+        .collect();
+
+    let default_name = fetched_templates
+        .default_template()
+        .map(|template| template.name.as_str());
+
+    let starting_cursor = options
+        .iter()
+        .position(|option| Some(option.template.name.as_str()) == default_name)
+        .unwrap_or_default();
+
+    let selected = Select::new("Which template would you like to use?", options)
+        .with_help_message(
+            "Press ↑/↓ to change selection. Press enter to confirm. Pass --template to skip this question.",
+        )
+        .with_starting_cursor(starting_cursor)
+        .with_formatter(&|option| option.value.template.display_name().to_string())
+        .with_page_size(10)
+        .prompt()?;
+
+    return Ok(selected.template);
+}
+
 async fn get_application(
     application_arg: Option<String>,
-    tenant: &TenantResponse,
-) -> Result<ApplicationResponse, Box<dyn Error>> {
+    application_name: Option<String>,
+    bundle_id: Option<String>,
+    tenant: &Resolved<TenantResponse>,
+    interactive: bool,
+    dry_run: bool,
+) -> Result<Resolved<ApplicationResponse>, ParraCliError> {
     // The user provided a application ID directly.
     if let Some(application_id) = application_arg {
+        if tenant.is_placeholder() {
+            return Err(ParraCliError::InvalidInput(format!(
+                "The application {} can't belong to a workspace that hasn't been created yet.",
+                application_id
+            )));
+        }
+
         let _ = api::report_event(
             "cli_bootstrap_application_provided",
             Some(HashMap::from([("application_id", application_id.as_str())])),
         );
 
-        return api::get_application(&tenant.id, &application_id).await;
+        return api::get_application(&tenant.id, &application_id)
+            .await
+            .map(Resolved::Existing);
     }
 
-    let applications = api::paginate_applications(&tenant.id).await?;
+    // A workspace that a dry run pretended to create has no applications, and
+    // doesn't exist to list them from.
+    let applications = if tenant.is_placeholder() {
+        vec![]
+    } else {
+        api::paginate_applications(&tenant.id).await?
+    };
+
+    if let Some(application_name) = application_name {
+        return find_or_create_application(
+            applications,
+            &application_name,
+            bundle_id,
+            tenant,
+            dry_run,
+        )
+        .await;
+    }
+
+    if !interactive {
+        if applications.len() == 1 {
+            return Ok(Resolved::Existing(applications[0].clone()));
+        }
+
+        let message = if applications.is_empty() {
+            "The workspace has no applications yet. Pass --app-name to create one."
+        } else {
+            "The workspace has multiple applications. Pass --application-id or --app-name to choose one."
+        };
+
+        return Err(ParraCliError::InvalidInput(message.to_string()));
+    }
 
     if applications.is_empty() {
-        return create_new_application(&tenant).await;
+        return create_new_application(&tenant, bundle_id, dry_run).await;
     }
 
     let _ = api::report_event("cli_bootstrap_application_existed", None);
@@ -704,9 +1420,9 @@ async fn get_application(
         let use_only = Confirm::new(&prompt).with_default(true).prompt()?;
 
         if use_only {
-            return Ok(application);
+            return Ok(Resolved::Existing(application));
         } else {
-            return create_new_application(&tenant).await;
+            return create_new_application(&tenant, bundle_id, dry_run).await;
         }
     }
 
@@ -725,23 +1441,29 @@ async fn get_application(
                 .prompt();
 
         match selected_application {
-            Ok(application) => return Ok(application),
+            Ok(application) => return Ok(Resolved::Existing(application)),
             Err(error) => Err(error.into()),
         }
     } else {
-        return create_new_application(&tenant).await;
+        return create_new_application(&tenant, bundle_id, dry_run).await;
     }
 }
 
 fn get_project_path(
     project_path_arg: Option<String>,
     app_name: &str,
-) -> String {
+    interactive: bool,
+) -> Result<String, ParraCliError> {
     if let Some(project_path) = project_path_arg {
-        return project_path;
+        return Ok(project_path);
     }
 
     let default_path = format!("./{}", app_name);
+
+    if !interactive {
+        return Ok(default_path);
+    }
+
     let default_message = format!("defaults to {}", default_path);
 
     let project_path =
@@ -749,15 +1471,15 @@ fn get_project_path(
             .with_default(&default_path)
             .with_validator(MinLengthValidator::new(1))
             .with_help_message(&default_message)
-            .prompt()
-            .unwrap();
+            .prompt()?;
 
-    return project_path;
+    return Ok(project_path);
 }
 
 async fn create_new_tenant(
     others_exist: bool,
-) -> Result<TenantResponse, Box<dyn Error>> {
+    dry_run: bool,
+) -> Result<Resolved<TenantResponse>, ParraCliError> {
     let message = if others_exist {
         "What would you like to call your workspace?"
     } else {
@@ -768,61 +1490,199 @@ async fn create_new_tenant(
         .with_validator(MinLengthValidator::new(1))
         .prompt()?;
 
-    return api::create_tenant(&name.trim()).await;
+    return create_tenant(&name.trim(), dry_run).await;
+}
+
+/// Uses the workspace with the given name, creating it if none exists.
+async fn find_or_create_tenant(
+    tenants: Vec<TenantResponse>,
+    name: &str,
+    dry_run: bool,
+) -> Result<Resolved<TenantResponse>, ParraCliError> {
+    let name = name.trim();
+
+    if let Some(tenant) = tenants
+        .into_iter()
+        .find(|tenant| tenant.name.trim().eq_ignore_ascii_case(name))
+    {
+        return Ok(Resolved::Existing(tenant));
+    }
+
+    if name.is_empty() {
+        return Err(ParraCliError::InvalidInput(
+            "The workspace name can't be empty.".to_string(),
+        ));
+    }
+
+    println!("Creating workspace \"{}\"", name);
+
+    return create_tenant(name, dry_run).await;
+}
+
+/// Uses the application with the given name, creating it if none exists.
+async fn find_or_create_application(
+    applications: Vec<ApplicationResponse>,
+    name: &str,
+    bundle_id: Option<String>,
+    tenant: &TenantResponse,
+    dry_run: bool,
+) -> Result<Resolved<ApplicationResponse>, ParraCliError> {
+    let name = name.trim();
+
+    if let Some(application) = applications
+        .into_iter()
+        .find(|application| application.name.trim().eq_ignore_ascii_case(name))
+    {
+        return Ok(Resolved::Existing(application));
+    }
+
+    validate_app_name(name).map_err(ParraCliError::InvalidInput)?;
+
+    let bundle_id =
+        bundle_id.unwrap_or_else(|| suggested_bundle_id(tenant, name));
+    let bundle_id = bundle_id.trim();
+
+    validate_bundle_id(bundle_id).map_err(ParraCliError::InvalidInput)?;
+
+    println!("Creating application \"{}\" ({})", name, bundle_id);
+
+    return create_application(tenant, name, bundle_id, dry_run).await;
 }
 
 async fn create_new_application(
     tenant: &TenantResponse,
-) -> Result<ApplicationResponse, Box<dyn Error>> {
+    bundle_id: Option<String>,
+    dry_run: bool,
+) -> Result<Resolved<ApplicationResponse>, ParraCliError> {
     let name = Text::new("What would you like to call your application?")
         .with_validator(MinLengthValidator::new(1))
-        .with_validator(|input: &str| {
-            let re: Regex =
-                Regex::new(r"[^a-zA-Z0-9\s-]").unwrap();
-
-            if re.is_match(input) {
-                Ok(Validation::Invalid("The app name must contain only alphanumeric characters (A–Z, a–z, and 0–9), hyphens (-), and spaces.".into()))
-            } else {
-                Ok(Validation::Valid)
-            }
+        .with_validator(|input: &str| match validate_app_name(input) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(message) => Ok(Validation::Invalid(message.into())),
         })
-
         .prompt()?;
 
+    let bundle_id = if let Some(bundle_id) = bundle_id {
+        validate_bundle_id(bundle_id.trim())
+            .map_err(ParraCliError::InvalidInput)?;
+
+        bundle_id
+    } else {
+        let suggested_bundle_id = suggested_bundle_id(tenant, &name);
+
+        Text::new("What would you like your bundle ID to be?")
+            .with_default(&suggested_bundle_id)
+            .with_help_message(
+                format!("defaults to {}", &suggested_bundle_id).as_str(),
+            )
+            .with_validator(MinLengthValidator::new(5)) // min for x.y.z
+            .with_validator(MaxLengthValidator::new(155))
+            .with_validator(|input: &str| match validate_bundle_id(input) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(message) => Ok(Validation::Invalid(message.into())),
+            })
+            .prompt()?
+    };
+
+    return create_application(
+        tenant,
+        &name.trim(),
+        &bundle_id.trim(),
+        dry_run,
+    )
+    .await;
+}
+
+/// Creates a workspace, or only pretends to during a dry run.
+async fn create_tenant(
+    name: &str,
+    dry_run: bool,
+) -> Result<Resolved<TenantResponse>, ParraCliError> {
+    if !dry_run {
+        return api::create_tenant(name).await.map(Resolved::Existing);
+    }
+
+    println!("Dry run: would create workspace \"{}\"", name);
+
+    return Ok(Resolved::DryRunPlaceholder(TenantResponse {
+        id: DRY_RUN_PLACEHOLDER_ID.to_owned(),
+        name: name.to_owned(),
+        subdomain: None,
+        logo: None,
+        domains: vec![],
+    }));
+}
+
+/// Creates an application, or only pretends to during a dry run.
+async fn create_application(
+    tenant: &TenantResponse,
+    name: &str,
+    bundle_id: &str,
+    dry_run: bool,
+) -> Result<Resolved<ApplicationResponse>, ParraCliError> {
+    if !dry_run {
+        return api::create_application(&tenant.id, name, bundle_id)
+            .await
+            .map(Resolved::Existing);
+    }
+
+    println!(
+        "Dry run: would create application \"{}\" ({})",
+        name, bundle_id
+    );
+
+    return Ok(Resolved::DryRunPlaceholder(ApplicationResponse {
+        id: DRY_RUN_PLACEHOLDER_ID.to_owned(),
+        name: name.to_owned(),
+        description: None,
+        r#type: "ios".to_owned(),
+        tenant_id: tenant.id.clone(),
+        ios: Some(ApplicationIosConfig {
+            bundle_id: bundle_id.to_owned(),
+            app_id: None,
+            team_id: None,
+        }),
+        icon: None,
+    }));
+}
+
+fn suggested_bundle_id(tenant: &TenantResponse, app_name: &str) -> String {
     let tenant_slug = slugify!(&tenant.name);
-    let app_name_slug = slugify!(&name);
-
-    let suggested_bundle_id = format!("com.{}.{}", tenant_slug, app_name_slug);
-
-    let bundle_id = Text::new("What would you like your bundle ID to be?")
-        .with_default(&suggested_bundle_id)
-        .with_help_message(format!("defaults to {}", &suggested_bundle_id).as_str())
-        .with_validator(MinLengthValidator::new(5)) // min for x.y.z
-        .with_validator(MaxLengthValidator::new(155))
-        .with_validator(|input: &str| {
-            let re =
-                Regex::new(r"^[a-zA-Z0-9-]+(\.[a-zA-Z0-9-]+){2,}$").unwrap();
-
-            if re.is_match(input) {
-                Ok(Validation::Valid)
-            } else {
-                Ok(Validation::Invalid("The bundle ID string must contain only alphanumeric characters (A–Z, a–z, and 0–9), hyphens (-), and periods (.). Typically, you use a reverse-DNS format for bundle ID strings. Bundle IDs are case-insensitive.".into()))
-            }
-        })
-        .prompt()?;
+    let app_name_slug = slugify!(app_name);
+
+    return format!("com.{}.{}", tenant_slug, app_name_slug);
+}
 
-    let new_application =
-        api::create_application(&tenant.id, &name.trim(), &bundle_id.trim())
-            .await?;
+fn validate_app_name(name: &str) -> Result<(), String> {
+    let re = Regex::new(r"[^a-zA-Z0-9\s-]").unwrap();
 
-    return Ok(new_application);
+    if name.trim().is_empty() {
+        Err("The app name can't be empty.".to_string())
+    } else if re.is_match(name) {
+        Err("The app name must contain only alphanumeric characters (A–Z, a–z, and 0–9), hyphens (-), and spaces.".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_bundle_id(bundle_id: &str) -> Result<(), String> {
+    let re = Regex::new(r"^[a-zA-Z0-9-]+(\.[a-zA-Z0-9-]+){2,}$").unwrap();
+
+    if bundle_id.len() > 155 {
+        Err("The bundle ID can't be longer than 155 characters.".to_string())
+    } else if re.is_match(bundle_id) {
+        Ok(())
+    } else {
+        Err("The bundle ID string must contain only alphanumeric characters (A–Z, a–z, and 0–9), hyphens (-), and periods (.). Typically, you use a reverse-DNS format for bundle ID strings. Bundle IDs are case-insensitive.".to_string())
+    }
 }
 
 async fn open_project(
     project_path: &PathBuf,
     context: &ProjectContext,
-) -> Result<(), Box<dyn Error>> {
-    let binding = project_path.to_str().unwrap().to_owned() + ".xcodeproj";
+    launch_xcode: bool,
+) -> Result<(), ParraCliError> {
+    let binding = project_path.to_string_lossy().into_owned() + ".xcodeproj";
 
     let full_path: String =
         if !binding.starts_with("/") && !binding.starts_with(".") {
@@ -831,8 +1691,10 @@ async fn open_project(
             binding
         };
     let path_clone = &full_path.clone();
-    let relative_project_dir =
-        Path::new(&path_clone).parent().unwrap().to_string_lossy();
+    let relative_project_dir = Path::new(&path_clone)
+        .parent()
+        .unwrap_or(Path::new("."))
+        .to_string_lossy();
 
     let absolute_path = fs::canonicalize(full_path)?;
     let mut project_dir_path = absolute_path.clone();
@@ -884,18 +1746,22 @@ async fn open_project(
 
     println!("{}", complete_message);
 
+    if !launch_xcode {
+        return Ok(());
+    }
+
     let xcode_path_output = Command::new("xcrun")
         .arg("xcode-select")
         .arg("--print-path")
         .output()
-        .expect("Failed to get Xcode install location");
+        .map_err(|error| ParraCliError::external_tool("xcrun", error))?;
 
     let xcode_path = String::from_utf8(xcode_path_output.stdout)?
         .trim()
         .to_string()
         .split(".app")
         .next()
-        .unwrap()
+        .unwrap_or_default()
         .to_owned()
         + ".app";
 
@@ -904,7 +1770,7 @@ async fn open_project(
         .arg("-a")
         .arg(xcode_path)
         .output()
-        .expect("Failed to open project in Xcode");
+        .map_err(|error| ParraCliError::external_tool("open", error))?;
 
     if !output.status.success() {
         println!("Couldn't open your project in Xcode automatically. Open your project at: {}", path_clone);
@@ -917,7 +1783,7 @@ async fn open_project(
 
 extern crate dirs; // 1.0.4
 
-fn expand_tilde<P: AsRef<Path>>(path_user_input: P) -> Option<PathBuf> {
+pub fn expand_tilde<P: AsRef<Path>>(path_user_input: P) -> Option<PathBuf> {
     let p = path_user_input.as_ref();
 
     if !p.starts_with("~") {
@@ -940,15 +1806,29 @@ fn expand_tilde<P: AsRef<Path>>(path_user_input: P) -> Option<PathBuf> {
     })
 }
 
-fn get_template_path(template_name: &str) -> Result<PathBuf, Box<dyn Error>> {
-    let repo_path = get_repo_root_path()?;
-    let relative_path = PathBuf::from(format!("templates/{}/", template_name));
-    let full_path = repo_path.join(&relative_path);
+/// Where templates are loaded from, and whether their local package
+/// definitions are used. Debug builds use the templates in this repo, so that
+/// template changes can be tested without publishing them.
+pub fn configured_template_source(
+    template_source: Option<&str>,
+) -> Result<(TemplateSource, bool), ParraCliError> {
+    match template_source {
+        Some(template_source) => {
+            return Ok((TemplateSource::parse(template_source)?, false));
+        }
+        None if cfg!(debug_assertions) => {
+            println!("Running in DEBUG mode. Using local templates!!!");
 
-    return Ok(full_path);
+            return Ok((
+                TemplateSource::Local(get_templates_dir_path()?),
+                true,
+            ));
+        }
+        None => return Ok((TemplateSource::default_remote(), false)),
+    }
 }
 
-fn get_templates_dir_path() -> Result<PathBuf, Box<dyn Error>> {
+fn get_templates_dir_path() -> Result<PathBuf, ParraCliError> {
     let repo_path = get_repo_root_path()?;
     let relative_path = PathBuf::from("templates/");
     let full_path = repo_path.join(&relative_path);
@@ -956,7 +1836,7 @@ fn get_templates_dir_path() -> Result<PathBuf, Box<dyn Error>> {
     return Ok(full_path);
 }
 
-fn get_sample_path() -> Result<PathBuf, Box<dyn Error>> {
+fn get_sample_path() -> Result<PathBuf, ParraCliError> {
     let repo_path = get_repo_root_path()?;
     let relative_path = PathBuf::from("sample/");
     let full_path = repo_path.join(&relative_path);
@@ -966,12 +1846,15 @@ fn get_sample_path() -> Result<PathBuf, Box<dyn Error>> {
     return Ok(full_path);
 }
 
-fn get_repo_root_path() -> Result<PathBuf, Box<dyn Error>> {
+fn get_repo_root_path() -> Result<PathBuf, ParraCliError> {
     let current_dir = env::current_dir()?;
     let repo = Repository::discover(&current_dir)?;
-    let repo_path = repo
-        .workdir()
-        .ok_or("Could not find the working directory for the repo.")?;
+    let repo_path = repo.workdir().ok_or_else(|| {
+        ParraCliError::external_tool(
+            "git",
+            "Could not find the working directory for the repo.",
+        )
+    })?;
 
     return Ok(repo_path.to_path_buf());
 }
@@ -1,28 +1,31 @@
-use crate::{api, auth};
+use crate::errors::ParraCliError;
+use crate::{api, auth, config, profiles};
 
-pub async fn execute_login() {
-    let result = auth::perform_device_authentication().await;
+pub async fn execute_login(token: Option<String>) -> Result<(), ParraCliError> {
+    let (credential, success) = match token {
+        Some(token) => (auth::login_with_token(&token).await?, true),
+        None => auth::perform_device_authentication().await?,
+    };
 
-    match result {
-        Ok((credential, success)) => {
-            let user = api::get_current_user(&credential).await.unwrap();
+    let user = api::get_current_user(&credential).await?;
 
-            if success {
-                if let Some(email) = user.email {
-                    println!("Successfully logged in as {}", email)
-                } else {
-                    println!("Successfully logged in")
-                }
-            } else {
-                if let Some(email) = user.email {
-                    println!("Already logged in as {}", email)
-                } else {
-                    println!("Already logged in")
-                }
-            }
+    if let Err(error) = profiles::register_profile(&config::current().profile) {
+        eprintln!("Failed to save profile: {}", error)
+    }
+
+    if success {
+        if let Some(email) = user.email {
+            println!("Successfully logged in as {}", email)
+        } else {
+            println!("Successfully logged in")
         }
-        Err(error) => {
-            eprintln!("Failed to login: {}", error)
+    } else {
+        if let Some(email) = user.email {
+            println!("Already logged in as {}", email)
+        } else {
+            println!("Already logged in")
         }
     }
+
+    Ok(())
 }
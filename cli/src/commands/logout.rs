@@ -1,18 +1,63 @@
-use crate::auth;
+use crate::errors::ParraCliError;
+use crate::profiles::{self, DEFAULT_PROFILE_NAME};
+use crate::{auth, config};
 
-pub fn execute_logout() {
-    let result = auth::logout();
+pub async fn execute_logout(all_profiles: bool) -> Result<(), ParraCliError> {
+    let current_profile = config::current().profile.clone();
 
-    match result {
-        Ok(success) => {
-            if success {
-                println!("Successfully logged out.")
-            } else {
-                println!("Not currently logged in.")
+    let profile_names: Vec<String> = if all_profiles {
+        let mut names: Vec<String> =
+            profiles::read_profiles()?.profiles.into_keys().collect();
+
+        for name in [DEFAULT_PROFILE_NAME.to_string(), current_profile] {
+            if !names.contains(&name) {
+                names.push(name);
             }
         }
-        Err(error) => {
-            eprintln!("Failed to logout: {}", error)
+
+        names
+    } else {
+        vec![current_profile]
+    };
+
+    let mut failed = false;
+
+    for name in &profile_names {
+        let outcome = auth::logout_profile(name).await;
+
+        if all_profiles {
+            println!("Profile \"{}\":", name);
+        }
+
+        match outcome.revocation {
+            Some(Ok(_)) => println!("Revoked refresh token."),
+            Some(Err(error)) => {
+                failed = true;
+                eprintln!("Failed to revoke refresh token: {}", error)
+            }
+            None => {}
+        }
+
+        match outcome.deletion {
+            Ok(success) => {
+                if success {
+                    println!("Successfully logged out.")
+                } else {
+                    println!("Not currently logged in.")
+                }
+            }
+            Err(error) => {
+                failed = true;
+                eprintln!("Failed to remove local credential: {}", error)
+            }
         }
     }
+
+    if failed {
+        return Err(ParraCliError::Auth(
+            "Logout did not complete successfully".to_string(),
+        ));
+    }
+
+    Ok(())
 }
@@ -1,3 +1,7 @@
 pub mod bootstrap;
 pub mod login;
 pub mod logout;
+pub mod profiles;
+pub mod render;
+pub mod template;
+pub mod whoami;
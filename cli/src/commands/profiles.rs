@@ -0,0 +1,111 @@
+use crate::arg_parser::{ProfileRemoveArgs, ProfileUseArgs, ProfilesCommand};
+use crate::errors::ParraCliError;
+use crate::profiles::{self, DEFAULT_PROFILE_NAME};
+use crate::{auth, config};
+
+pub async fn execute_profiles(
+    command: ProfilesCommand,
+) -> Result<(), ParraCliError> {
+    match command {
+        ProfilesCommand::List => list_profiles(),
+        ProfilesCommand::Use(args) => use_profile(args),
+        ProfilesCommand::Remove(args) => remove_profile(args).await,
+    }
+}
+
+fn list_profiles() -> Result<(), ParraCliError> {
+    let profiles_file = profiles::read_profiles()?;
+    let active = &config::current().profile;
+
+    let mut names: Vec<String> =
+        profiles_file.profiles.keys().cloned().collect();
+    if !names.contains(active) {
+        names.push(active.clone());
+    }
+    if !names.iter().any(|name| name == DEFAULT_PROFILE_NAME) {
+        names.insert(0, DEFAULT_PROFILE_NAME.to_string());
+    }
+
+    for name in names {
+        let marker = if &name == active { "*" } else { " " };
+        let logged_in = matches!(
+            auth::get_persisted_credential_for_profile(&name),
+            Ok(Some(_))
+        );
+        let profile = profiles_file
+            .profiles
+            .get(&name)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut details = vec![if logged_in {
+            "logged in".to_string()
+        } else {
+            "logged out".to_string()
+        }];
+
+        if let Some(tenant_id) = profile.tenant_id {
+            details.push(format!("tenant: {}", tenant_id));
+        }
+
+        if let Some(application_id) = profile.application_id {
+            details.push(format!("application: {}", application_id));
+        }
+
+        println!("{} {} ({})", marker, name, details.join(", "));
+    }
+
+    Ok(())
+}
+
+fn use_profile(args: ProfileUseArgs) -> Result<(), ParraCliError> {
+    let mut profiles_file = profiles::read_profiles()?;
+
+    let profile = profiles_file.profiles.entry(args.name.clone()).or_default();
+
+    if args.tenant_id.is_some() {
+        profile.tenant_id = args.tenant_id;
+    }
+
+    if args.application_id.is_some() {
+        profile.application_id = args.application_id;
+    }
+
+    profiles_file.active = Some(args.name.clone());
+
+    profiles::write_profiles(&profiles_file)?;
+
+    println!("Now using profile \"{}\".", args.name);
+
+    Ok(())
+}
+
+async fn remove_profile(args: ProfileRemoveArgs) -> Result<(), ParraCliError> {
+    let mut profiles_file = profiles::read_profiles()?;
+
+    let existed = profiles_file.profiles.remove(&args.name).is_some();
+    let outcome = auth::logout_profile(&args.name).await;
+
+    if let Some(Err(error)) = outcome.revocation {
+        eprintln!("Failed to revoke refresh token: {}", error);
+    }
+
+    let was_logged_in = outcome.deletion?;
+
+    if !existed && !was_logged_in {
+        return Err(ParraCliError::InvalidInput(format!(
+            "No profile named \"{}\" exists.",
+            args.name
+        )));
+    }
+
+    if profiles_file.active.as_deref() == Some(args.name.as_str()) {
+        profiles_file.active = None;
+    }
+
+    profiles::write_profiles(&profiles_file)?;
+
+    println!("Removed profile \"{}\".", args.name);
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::commands::bootstrap::resolve_deployment_target;
+use crate::errors::ParraCliError;
+use crate::project_generator::{generator, inputs};
+use crate::types::templates::{ProjectContext, TemplateConfig};
+use std::fs;
+use std::path::PathBuf;
+
+/// Renders a local template with a context read from a file. Unlike
+/// bootstrap, this never talks to the Parra API and doesn't run xcodegen,
+/// download the app icon or resolve Swift packages, so it works offline and on
+/// machines without Xcode.
+pub fn execute_render(
+    template_dir: PathBuf,
+    context_path: PathBuf,
+    output_dir: PathBuf,
+    use_local_packages: bool,
+    overwrite: bool,
+    inputs: Vec<String>,
+    inputs_path: Option<PathBuf>,
+) -> Result<(), ParraCliError> {
+    let template_dir = fs::canonicalize(&template_dir)
+        .map_err(|error| ParraCliError::filesystem(&template_dir, error))?;
+
+    let template_app_dir = template_dir.join("App");
+    if !template_app_dir.is_dir() {
+        return Err(ParraCliError::InvalidInput(format!(
+            "{} is not a template. Templates must contain an App directory.",
+            template_dir.display()
+        )));
+    }
+
+    // Templates may link to files in their sibling templates.
+    let templates_dir = template_dir
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| template_dir.clone());
+
+    // The template's own config is used rather than one in the context, so
+    // that changes to it are rendered.
+    let config =
+        generator::read_template_config(&template_dir, &templates_dir)?;
+    let mut context = read_context(&context_path, &config)?;
+
+    let template_name = template_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    context.app.deployment_target = resolve_deployment_target(
+        Some(context.app.deployment_target),
+        &config,
+        &template_name,
+    )?;
+    let input_answers = inputs::collect_answers(&inputs, inputs_path.as_ref())?;
+
+    let project_spec = generator::read_project_spec(
+        &template_dir,
+        &templates_dir,
+        use_local_packages,
+    )?;
+
+    prepare_output_dir(&output_dir, overwrite)?;
+
+    // Renders non-interactively so that it can be used from CI. Template
+    // inputs without an answer use their defaults.
+    generator::render_project(
+        &output_dir,
+        &templates_dir,
+        &template_app_dir,
+        &project_spec,
+        &context,
+        false,
+        &input_answers,
+    )?;
+
+    println!(
+        "Rendered {} to {}",
+        template_dir.display(),
+        output_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Reads the context, replacing any `config` in it with the template's.
+fn read_context(
+    path: &PathBuf,
+    config: &TemplateConfig,
+) -> Result<ProjectContext, ParraCliError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+    let invalid = |error: serde_json::Error| {
+        ParraCliError::InvalidInput(format!(
+            "Invalid context at {}: {}",
+            path.display(),
+            error
+        ))
+    };
+
+    let mut context: serde_json::Value =
+        serde_json::from_str(&contents).map_err(invalid)?;
+
+    if let Some(context) = context.as_object_mut() {
+        context.insert("config".to_string(), serde_json::to_value(config)?);
+    }
+
+    return serde_json::from_value::<ProjectContext>(context).map_err(invalid);
+}
+
+fn prepare_output_dir(
+    output_dir: &PathBuf,
+    overwrite: bool,
+) -> Result<(), ParraCliError> {
+    if !output_dir.exists() {
+        return Ok(());
+    }
+
+    let is_empty = fs::read_dir(output_dir)
+        .map_err(|error| ParraCliError::filesystem(output_dir, error))?
+        .next()
+        .is_none();
+
+    if is_empty {
+        return Ok(());
+    }
+
+    if !overwrite {
+        return Err(ParraCliError::InvalidInput(format!(
+            "{} is not empty. Pass --overwrite to replace it.",
+            output_dir.display()
+        )));
+    }
+
+    return fs::remove_dir_all(output_dir)
+        .map_err(|error| ParraCliError::filesystem(output_dir, error));
+}
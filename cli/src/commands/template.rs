@@ -0,0 +1,125 @@
+use crate::arg_parser::TemplateCacheCommand;
+use crate::commands::bootstrap;
+use crate::errors::ParraCliError;
+use crate::project_generator::lint;
+use crate::{template_cache, template_source};
+use std::path::PathBuf;
+
+pub fn execute_lint(template_dir: PathBuf) -> Result<(), ParraCliError> {
+    let issues = lint::lint_template(&template_dir)?;
+
+    if issues.is_empty() {
+        println!("No problems found in {}", template_dir.display());
+
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("{}", issue);
+    }
+
+    return Err(ParraCliError::TemplateRendering(format!(
+        "Found {} problem{} in {}",
+        issues.len(),
+        if issues.len() == 1 { "" } else { "s" },
+        template_dir.display()
+    )));
+}
+
+pub async fn execute_list(
+    template_source: Option<String>,
+) -> Result<(), ParraCliError> {
+    let (template_source, _) =
+        bootstrap::configured_template_source(template_source.as_deref())?;
+
+    let fetched_templates =
+        template_source::fetch_templates(&template_source).await?;
+    let default_template = fetched_templates
+        .default_template()
+        .map(|template| template.name.clone());
+
+    println!("Templates in {}:\n", fetched_templates.source);
+
+    for template in &fetched_templates.templates {
+        let config = &template.config;
+        let marker = if default_template.as_ref() == Some(&template.name) {
+            " (default)"
+        } else {
+            ""
+        };
+
+        println!("{} - {}{}", template.name, template.display_name(), marker);
+
+        if let Some(description) = &config.description {
+            println!("  {}", description);
+        }
+
+        if !config.required_tabs.is_empty() {
+            println!("  Tabs: {}", config.required_tabs.join(", "));
+        }
+
+        if let Some(min_sdk_version) = &config.min_sdk_version {
+            println!("  Requires Parra SDK {} or later", min_sdk_version);
+        }
+
+        println!();
+    }
+
+    return Ok(());
+}
+
+pub fn execute_cache(
+    command: TemplateCacheCommand,
+) -> Result<(), ParraCliError> {
+    match command {
+        TemplateCacheCommand::List => list_cache(),
+        TemplateCacheCommand::Clear => clear_cache(),
+    }
+}
+
+fn list_cache() -> Result<(), ParraCliError> {
+    let entries = template_cache::list()?;
+
+    if entries.is_empty() {
+        println!("No templates are cached.");
+
+        return Ok(());
+    }
+
+    for (entry, intact) in entries {
+        let mut details = vec![];
+
+        if let Some(commit) = &entry.metadata.commit {
+            details.push(format!("commit {}", &commit[..commit.len().min(12)]));
+        }
+
+        if let Some(fetched_at) = entry.fetched_at() {
+            details.push(format!(
+                "fetched {}",
+                fetched_at.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+
+        if !intact {
+            details.push("modified, will be downloaded again".to_string());
+        }
+
+        println!("{} ({})", entry.metadata.source, details.join(", "));
+    }
+
+    println!("\nCached in {}", template_cache::cache_dir().display());
+
+    return Ok(());
+}
+
+fn clear_cache() -> Result<(), ParraCliError> {
+    let count = template_cache::clear()?;
+
+    println!(
+        "Removed {} cached template source{}.",
+        count,
+        if count == 1 { "" } else { "s" }
+    );
+
+    return Ok(());
+}
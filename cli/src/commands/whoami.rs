@@ -0,0 +1,93 @@
+use crate::errors::ParraCliError;
+use crate::types::auth::AuthStatus;
+use crate::{api, auth, config, credential_store};
+use chrono::{DateTime, Utc};
+
+pub async fn execute_whoami(json: bool) -> Result<(), ParraCliError> {
+    let config = config::current();
+
+    let credential_source = if auth::get_env_token().is_some() {
+        auth::TOKEN_ENV_VAR.to_string()
+    } else {
+        credential_store::selected_backend()?.to_string()
+    };
+
+    let mut status = AuthStatus {
+        logged_in: false,
+        profile: config.profile.clone(),
+        credential_source,
+        user: None,
+        token_expires_at: None,
+        refresh_required: false,
+        can_refresh: false,
+    };
+
+    let Some(existing_credential) = auth::get_existing_credential().await?
+    else {
+        print_status(&status, json)?;
+
+        return Err(ParraCliError::Auth("Not logged in".to_string()));
+    };
+
+    status.refresh_required = auth::is_refresh_required(&existing_credential)?;
+    status.can_refresh = !existing_credential.refresh_token.is_empty();
+
+    // Refreshing goes through the usual flow, which stores the new credential.
+    // Servers that rotate refresh tokens invalidate the old one, so keeping
+    // only the refreshed credential in memory would leave the stored one
+    // unusable.
+    let credential = if !status.refresh_required {
+        existing_credential
+    } else if status.can_refresh {
+        auth::perform_device_authentication().await?.0
+    } else {
+        print_status(&status, json)?;
+
+        return Err(ParraCliError::Auth("The stored access token has expired and can not be refreshed. Log in again or provide a new token.".to_string()));
+    };
+
+    status.user = Some(api::get_current_user(&credential).await?);
+    status.token_expires_at =
+        DateTime::<Utc>::from_timestamp(credential.expiry as i64, 0)
+            .map(|date| date.to_rfc3339());
+    status.logged_in = true;
+
+    print_status(&status, json)
+}
+
+fn print_status(status: &AuthStatus, json: bool) -> Result<(), ParraCliError> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(status)?);
+
+        return Ok(());
+    }
+
+    println!("Profile: {}", status.profile);
+    println!("Credential source: {}", status.credential_source);
+
+    let Some(user) = &status.user else {
+        println!("Not logged in.");
+
+        return Ok(());
+    };
+
+    println!("User ID: {}", user.id);
+    println!("Name: {}", user.name.as_deref().unwrap_or("(not set)"));
+    println!("Email: {}", user.email.as_deref().unwrap_or("(not set)"));
+
+    if let Some(expires_at) = &status.token_expires_at {
+        println!("Token expires: {}", expires_at);
+    }
+
+    let refresh_message = if status.refresh_required {
+        "Done. The stored token had expired and was replaced with a refreshed one."
+    } else if status.can_refresh {
+        "Not required. The token will be refreshed automatically when it expires."
+    } else {
+        "Not possible. Log in again or provide a new token when this one expires."
+    };
+
+    println!("Refresh: {}", refresh_message);
+
+    Ok(())
+}
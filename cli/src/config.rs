@@ -0,0 +1,201 @@
+use crate::errors::ParraCliError;
+use crate::profiles;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub const DEFAULT_API_BASE_URL: &str = "https://api.parra.io/v1";
+pub const DEFAULT_AUTH_BASE_URL: &str = "https://auth.parra.io";
+pub const DEFAULT_AUTH0_CLIENT_ID: &str = "nD9GTUvvqCT0oWi34L2IdJiK0YjupSjY";
+
+const CONFIG_PATH_ENV_VAR: &str = "PARRA_CONFIG";
+const API_BASE_URL_ENV_VAR: &str = "PARRA_API_URL";
+const AUTH_BASE_URL_ENV_VAR: &str = "PARRA_AUTH_URL";
+const AUTH0_CLIENT_ID_ENV_VAR: &str = "PARRA_AUTH_CLIENT_ID";
+const PROFILE_ENV_VAR: &str = "PARRA_PROFILE";
+const CA_BUNDLE_ENV_VAR: &str = "PARRA_CA_BUNDLE";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The resolved configuration for the current invocation. Each value is taken
+/// from the first of these that provides it: command line flag, environment
+/// variable, config file, built in default.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub api_base_url: String,
+    pub auth_base_url: String,
+    pub auth0_client_id: String,
+    /// The credential store backend requested in the config file, if any. The
+    /// `PARRA_CREDENTIAL_STORE` environment variable takes precedence.
+    pub credential_store: Option<String>,
+    /// The named profile whose credential and defaults are used.
+    pub profile: String,
+    pub profiles_path: PathBuf,
+    /// A PEM file with additional root certificates to trust, e.g. for
+    /// networks that perform TLS interception.
+    pub ca_bundle: Option<PathBuf>,
+}
+
+/// Values provided on the command line, which take precedence over all other
+/// sources.
+#[derive(Debug, Default)]
+pub struct ConfigOverrides {
+    pub config_path: Option<PathBuf>,
+    pub api_base_url: Option<String>,
+    pub auth_base_url: Option<String>,
+    pub auth0_client_id: Option<String>,
+    pub profile: Option<String>,
+}
+
+/// The on-disk representation of `config.toml`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub api_base_url: Option<String>,
+    pub auth_base_url: Option<String>,
+    pub auth0_client_id: Option<String>,
+    pub credential_store: Option<String>,
+    pub ca_bundle: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            api_base_url: DEFAULT_API_BASE_URL.to_string(),
+            auth_base_url: DEFAULT_AUTH_BASE_URL.to_string(),
+            auth0_client_id: DEFAULT_AUTH0_CLIENT_ID.to_string(),
+            credential_store: None,
+            profile: profiles::DEFAULT_PROFILE_NAME.to_string(),
+            profiles_path: PathBuf::from("profiles.toml"),
+            ca_bundle: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn api_url(&self, endpoint: &str) -> String {
+        format!("{}{}", self.api_base_url.trim_end_matches('/'), endpoint)
+    }
+
+    pub fn auth_url(&self, endpoint: &str) -> String {
+        format!("{}{}", self.auth_base_url.trim_end_matches('/'), endpoint)
+    }
+
+    /// The credential store account for the selected profile.
+    pub fn credential_account(&self) -> String {
+        profiles::credential_account(&self.auth0_client_id, &self.profile)
+    }
+}
+
+/// Resolves the configuration and makes it available through `current()`.
+/// Must be called before any requests are made for the overrides to apply.
+pub fn init(overrides: ConfigOverrides) -> Result<(), ParraCliError> {
+    let config = load(overrides)?;
+
+    // Ignore the result. Only the first initialization wins.
+    let _ = CONFIG.set(config);
+
+    Ok(())
+}
+
+pub fn current() -> &'static Config {
+    CONFIG.get_or_init(|| load(ConfigOverrides::default()).unwrap_or_default())
+}
+
+pub fn config_file_path(
+    overrides: &ConfigOverrides,
+) -> Result<PathBuf, ParraCliError> {
+    if let Some(path) = &overrides.config_path {
+        return Ok(path.clone());
+    }
+
+    if let Ok(path) = env::var(CONFIG_PATH_ENV_VAR) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let config_dir = dirs::config_dir().ok_or_else(|| {
+        ParraCliError::InvalidInput(
+            "Could not determine the user's config directory.".to_string(),
+        )
+    })?;
+
+    Ok(config_dir.join("parra").join("config.toml"))
+}
+
+pub fn read_config_file(path: &PathBuf) -> Result<ConfigFile, ParraCliError> {
+    if !path.exists() {
+        return Ok(ConfigFile::default());
+    }
+
+    let contents = read_to_string(path)
+        .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+    toml::from_str::<ConfigFile>(&contents).map_err(|err| {
+        ParraCliError::InvalidInput(format!(
+            "Invalid config file at {}: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+fn load(overrides: ConfigOverrides) -> Result<Config, ParraCliError> {
+    let path = config_file_path(&overrides)?;
+    let file = read_config_file(&path)?;
+    let defaults = Config::default();
+
+    let profiles_path = path.with_file_name("profiles.toml");
+    let profiles_file = profiles::read_profiles_file(&profiles_path)?;
+
+    Ok(Config {
+        api_base_url: resolve(
+            overrides.api_base_url,
+            API_BASE_URL_ENV_VAR,
+            file.api_base_url,
+            defaults.api_base_url,
+        ),
+        auth_base_url: resolve(
+            overrides.auth_base_url,
+            AUTH_BASE_URL_ENV_VAR,
+            file.auth_base_url,
+            defaults.auth_base_url,
+        ),
+        auth0_client_id: resolve(
+            overrides.auth0_client_id,
+            AUTH0_CLIENT_ID_ENV_VAR,
+            file.auth0_client_id,
+            defaults.auth0_client_id,
+        ),
+        credential_store: file.credential_store,
+        profile: resolve(
+            overrides.profile,
+            PROFILE_ENV_VAR,
+            profiles_file.active,
+            defaults.profile,
+        ),
+        profiles_path,
+        ca_bundle: env::var(CA_BUNDLE_ENV_VAR)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(PathBuf::from)
+            .or(file.ca_bundle),
+    })
+}
+
+fn resolve(
+    flag: Option<String>,
+    env_var: &str,
+    file: Option<String>,
+    default: String,
+) -> String {
+    let nonempty = |value: &String| !value.trim().is_empty();
+
+    // Each source is filtered on its own, so that an empty flag doesn't hide
+    // the environment variable.
+    flag.filter(nonempty)
+        .or_else(|| env::var(env_var).ok().filter(nonempty))
+        .or(file.filter(nonempty))
+        .unwrap_or(default)
+}
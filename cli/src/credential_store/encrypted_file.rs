@@ -0,0 +1,243 @@
+use super::CredentialStore;
+use crate::errors::ParraCliError;
+use crate::types::auth::Credential;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// When set, the encryption key is derived from this value instead of the
+/// randomly generated key file. Useful on CI where the config directory is
+/// not preserved between runs but a secret can be injected.
+pub const CREDENTIAL_KEY_ENV_VAR: &str = "PARRA_CREDENTIAL_KEY";
+
+const KEY_FILE_NAME: &str = ".key";
+const NONCE_LENGTH: usize = 12;
+
+/// Stores each credential as an AES-256-GCM encrypted file under
+/// `<config dir>/parra/credentials`. Files are only readable by the current
+/// user.
+pub struct EncryptedFileCredentialStore {
+    service: String,
+    directory: PathBuf,
+}
+
+impl EncryptedFileCredentialStore {
+    pub fn new(service: &str) -> Result<Self, ParraCliError> {
+        let config_dir = dirs::config_dir().ok_or_else(|| {
+            ParraCliError::CredentialStore(
+                "Could not determine the user's config directory.".to_string(),
+            )
+        })?;
+
+        Ok(EncryptedFileCredentialStore::in_directory(
+            service,
+            config_dir.join("parra").join("credentials"),
+        ))
+    }
+
+    fn in_directory(service: &str, directory: PathBuf) -> Self {
+        EncryptedFileCredentialStore {
+            service: service.to_string(),
+            directory,
+        }
+    }
+
+    /// Account names are hex encoded, so that no two accounts can share a
+    /// file regardless of which characters they contain.
+    fn credential_path(&self, account: &str) -> PathBuf {
+        let encoded_account: String = account
+            .bytes()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        self.directory
+            .join(format!("{}-{}.enc", self.service, encoded_account))
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm, ParraCliError> {
+        let key_bytes: Vec<u8> = match env::var(CREDENTIAL_KEY_ENV_VAR) {
+            Ok(secret) => Sha256::digest(secret.as_bytes()).to_vec(),
+            Err(_) => self.read_or_create_key_file()?,
+        };
+
+        if key_bytes.len() != 32 {
+            return Err(ParraCliError::CredentialStore(format!(
+                "Credential key file at {} is corrupt. Delete it and log in again.",
+                self.directory.join(KEY_FILE_NAME).display()
+            )));
+        }
+
+        let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
+
+        Ok(Aes256Gcm::new(key))
+    }
+
+    fn read_or_create_key_file(&self) -> Result<Vec<u8>, ParraCliError> {
+        let key_path = self.directory.join(KEY_FILE_NAME);
+
+        if key_path.exists() {
+            return Ok(fs::read(key_path)?);
+        }
+
+        let key = Aes256Gcm::generate_key(OsRng);
+        write_private_file(&key_path, key.as_slice())?;
+
+        Ok(key.to_vec())
+    }
+}
+
+impl CredentialStore for EncryptedFileCredentialStore {
+    fn get(&self, account: &str) -> Result<Option<Credential>, ParraCliError> {
+        let path = self.credential_path(account);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read(&path)?;
+
+        if contents.len() <= NONCE_LENGTH {
+            return Err(ParraCliError::CredentialStore(format!(
+                "Credential file at {} is corrupt.",
+                path.display()
+            )));
+        }
+
+        let (nonce, ciphertext) = contents.split_at(NONCE_LENGTH);
+        let plaintext = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                ParraCliError::CredentialStore(format!(
+                    "Failed to decrypt credential file at {}. The key may have changed.",
+                    path.display()
+                ))
+            })?;
+
+        Ok(Some(serde_json::from_slice::<Credential>(&plaintext)?))
+    }
+
+    fn set(
+        &self,
+        account: &str,
+        credential: &Credential,
+    ) -> Result<(), ParraCliError> {
+        let serialized = serde_json::to_vec(credential)?;
+
+        let cipher = self.cipher()?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext =
+            cipher.encrypt(&nonce, serialized.as_slice()).map_err(|_| {
+                ParraCliError::CredentialStore(
+                    "Failed to encrypt credential".to_string(),
+                )
+            })?;
+
+        let mut contents = nonce.to_vec();
+        contents.extend(ciphertext);
+
+        write_private_file(&self.credential_path(account), &contents)?;
+
+        Ok(())
+    }
+
+    fn delete(&self, account: &str) -> Result<bool, ParraCliError> {
+        let path = self.credential_path(account);
+
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(path)?;
+
+        Ok(true)
+    }
+}
+
+fn write_private_file(
+    path: &Path,
+    contents: &[u8],
+) -> Result<(), ParraCliError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template_source::TempDir;
+
+    fn credential(token: &str) -> Credential {
+        return Credential {
+            token: token.to_string(),
+            refresh_token: format!("{}-refresh", token),
+            expiry: 1_700_000_000,
+        };
+    }
+
+    /// The directory is returned along with the store so that it outlives it.
+    fn store() -> (TempDir, EncryptedFileCredentialStore) {
+        let directory =
+            TempDir::create_in(&env::temp_dir(), "parra-credentials-test")
+                .unwrap();
+        let store = EncryptedFileCredentialStore::in_directory(
+            "parra_cli",
+            directory.path().to_path_buf(),
+        );
+
+        return (directory, store);
+    }
+
+    #[test]
+    fn round_trips_a_credential() {
+        let (_directory, store) = store();
+
+        store.set("id:default", &credential("first")).unwrap();
+
+        let stored = store.get("id:default").unwrap().unwrap();
+        assert_eq!(stored.token, "first");
+        assert_eq!(stored.refresh_token, "first-refresh");
+        assert_eq!(stored.expiry, 1_700_000_000);
+    }
+
+    #[test]
+    fn deletes_a_credential() {
+        let (_directory, store) = store();
+
+        store.set("id:default", &credential("first")).unwrap();
+
+        assert!(store.delete("id:default").unwrap());
+        assert!(store.get("id:default").unwrap().is_none());
+        assert!(!store.delete("id:default").unwrap());
+    }
+
+    #[test]
+    fn keeps_similar_accounts_apart() {
+        let (_directory, store) = store();
+
+        store.set("id:a_b", &credential("first")).unwrap();
+        store.set("id:a:b", &credential("second")).unwrap();
+
+        assert_eq!(store.get("id:a_b").unwrap().unwrap().token, "first");
+        assert_eq!(store.get("id:a:b").unwrap().unwrap().token, "second");
+    }
+}
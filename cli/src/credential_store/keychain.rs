@@ -0,0 +1,78 @@
+use super::CredentialStore;
+use crate::errors::ParraCliError;
+use crate::types::auth::Credential;
+
+pub struct KeychainCredentialStore {
+    service: String,
+}
+
+impl KeychainCredentialStore {
+    pub fn new(service: &str) -> Self {
+        KeychainCredentialStore {
+            service: service.to_string(),
+        }
+    }
+}
+
+impl CredentialStore for KeychainCredentialStore {
+    fn get(&self, account: &str) -> Result<Option<Credential>, ParraCliError> {
+        let result = security_framework::passwords::get_generic_password(
+            &self.service,
+            account,
+        );
+
+        match result {
+            Ok(data) => {
+                let data = String::from_utf8(data)?;
+
+                Ok(Some(serde_json::from_str::<Credential>(&data)?))
+            }
+            Err(error) => {
+                if error.code()
+                    == security_framework_sys::base::errSecItemNotFound
+                {
+                    Ok(None)
+                } else {
+                    Err(ParraCliError::CredentialStore(error.to_string()))
+                }
+            }
+        }
+    }
+
+    fn set(
+        &self,
+        account: &str,
+        credential: &Credential,
+    ) -> Result<(), ParraCliError> {
+        let serialized = serde_json::to_string(credential)?;
+
+        security_framework::passwords::set_generic_password(
+            &self.service,
+            account,
+            serialized.as_bytes(),
+        )
+        .map_err(|error| ParraCliError::CredentialStore(error.to_string()))?;
+
+        Ok(())
+    }
+
+    fn delete(&self, account: &str) -> Result<bool, ParraCliError> {
+        let result = security_framework::passwords::delete_generic_password(
+            &self.service,
+            account,
+        );
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(error) => {
+                if error.code()
+                    == security_framework_sys::base::errSecItemNotFound
+                {
+                    Ok(false)
+                } else {
+                    Err(ParraCliError::CredentialStore(error.to_string()))
+                }
+            }
+        }
+    }
+}
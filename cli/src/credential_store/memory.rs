@@ -0,0 +1,119 @@
+use super::CredentialStore;
+use crate::errors::ParraCliError;
+use crate::types::auth::Credential;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Shared between all instances so that a credential written by one command
+/// step can be read back by the next within the same process.
+static MEMORY_CREDENTIALS: Mutex<Option<HashMap<String, Credential>>> =
+    Mutex::new(None);
+
+pub struct MemoryCredentialStore {
+    service: String,
+}
+
+impl MemoryCredentialStore {
+    pub fn new(service: &str) -> Self {
+        MemoryCredentialStore {
+            service: service.to_string(),
+        }
+    }
+
+    fn entry_key(&self, account: &str) -> String {
+        format!("{}:{}", self.service, account)
+    }
+}
+
+fn store_unavailable() -> ParraCliError {
+    ParraCliError::CredentialStore(
+        "In-memory credential store is unavailable".to_string(),
+    )
+}
+
+impl CredentialStore for MemoryCredentialStore {
+    fn get(&self, account: &str) -> Result<Option<Credential>, ParraCliError> {
+        let guard =
+            MEMORY_CREDENTIALS.lock().map_err(|_| store_unavailable())?;
+
+        Ok(guard
+            .as_ref()
+            .and_then(|entries| entries.get(&self.entry_key(account)))
+            .cloned())
+    }
+
+    fn set(
+        &self,
+        account: &str,
+        credential: &Credential,
+    ) -> Result<(), ParraCliError> {
+        let mut guard =
+            MEMORY_CREDENTIALS.lock().map_err(|_| store_unavailable())?;
+
+        guard
+            .get_or_insert_with(HashMap::new)
+            .insert(self.entry_key(account), credential.clone());
+
+        Ok(())
+    }
+
+    fn delete(&self, account: &str) -> Result<bool, ParraCliError> {
+        let mut guard =
+            MEMORY_CREDENTIALS.lock().map_err(|_| store_unavailable())?;
+
+        Ok(guard
+            .as_mut()
+            .and_then(|entries| entries.remove(&self.entry_key(account)))
+            .is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credential(token: &str) -> Credential {
+        return Credential {
+            token: token.to_string(),
+            refresh_token: format!("{}-refresh", token),
+            expiry: 1_700_000_000,
+        };
+    }
+
+    // Entries are shared by the whole process, so each test uses its own
+    // service to stay independent of the others.
+
+    #[test]
+    fn round_trips_a_credential() {
+        let store = MemoryCredentialStore::new("memory_round_trip");
+
+        store.set("id:default", &credential("first")).unwrap();
+
+        let stored = store.get("id:default").unwrap().unwrap();
+        assert_eq!(stored.token, "first");
+        assert_eq!(stored.refresh_token, "first-refresh");
+        assert_eq!(stored.expiry, 1_700_000_000);
+    }
+
+    #[test]
+    fn deletes_a_credential() {
+        let store = MemoryCredentialStore::new("memory_delete");
+
+        store.set("id:default", &credential("first")).unwrap();
+
+        assert!(store.delete("id:default").unwrap());
+        assert!(store.get("id:default").unwrap().is_none());
+        assert!(!store.delete("id:default").unwrap());
+    }
+
+    #[test]
+    fn keeps_similar_accounts_apart() {
+        let store = MemoryCredentialStore::new("memory_collisions");
+
+        store.set("id:a_b", &credential("first")).unwrap();
+        store.set("id:a:b", &credential("second")).unwrap();
+
+        assert_eq!(store.get("id:a_b").unwrap().unwrap().token, "first");
+        assert_eq!(store.get("id:a:b").unwrap().unwrap().token, "second");
+    }
+}
@@ -0,0 +1,118 @@
+pub mod encrypted_file;
+#[cfg(target_os = "macos")]
+pub mod keychain;
+pub mod memory;
+
+use crate::config;
+use crate::errors::ParraCliError;
+use crate::types::auth::Credential;
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Environment variable used to select where credentials are stored. Accepts
+/// `keychain`, `file` or `memory`.
+pub const CREDENTIAL_STORE_ENV_VAR: &str = "PARRA_CREDENTIAL_STORE";
+
+/// A place that credentials can be persisted between invocations of the CLI.
+/// Entries are identified by an account name within the store's service.
+pub trait CredentialStore {
+    fn get(&self, account: &str) -> Result<Option<Credential>, ParraCliError>;
+
+    fn set(
+        &self,
+        account: &str,
+        credential: &Credential,
+    ) -> Result<(), ParraCliError>;
+
+    /// Returns true if a credential existed for the account and was removed.
+    fn delete(&self, account: &str) -> Result<bool, ParraCliError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CredentialStoreBackend {
+    /// The macOS Keychain. Only available on macOS.
+    Keychain,
+    /// An encrypted file in the user's config directory.
+    EncryptedFile,
+    /// Kept in memory for the lifetime of the process. Nothing is persisted.
+    Memory,
+}
+
+impl Default for CredentialStoreBackend {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            CredentialStoreBackend::Keychain
+        } else {
+            CredentialStoreBackend::EncryptedFile
+        }
+    }
+}
+
+impl FromStr for CredentialStoreBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "keychain" => Ok(CredentialStoreBackend::Keychain),
+            "file" | "encrypted-file" => {
+                Ok(CredentialStoreBackend::EncryptedFile)
+            }
+            "memory" => Ok(CredentialStoreBackend::Memory),
+            other => Err(format!(
+                "Unknown credential store \"{}\". Expected one of: keychain, file, memory.",
+                other
+            )),
+        }
+    }
+}
+
+impl Display for CredentialStoreBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let output = match self {
+            CredentialStoreBackend::Keychain => "keychain",
+            CredentialStoreBackend::EncryptedFile => "file",
+            CredentialStoreBackend::Memory => "memory",
+        };
+
+        write!(f, "{}", output)
+    }
+}
+
+/// Determines which backend to use, preferring the value of
+/// `PARRA_CREDENTIAL_STORE`, then the `credential_store` config file setting,
+/// and otherwise falling back to the platform default.
+pub fn selected_backend() -> Result<CredentialStoreBackend, ParraCliError> {
+    if let Ok(value) = env::var(CREDENTIAL_STORE_ENV_VAR) {
+        return CredentialStoreBackend::from_str(&value)
+            .map_err(ParraCliError::InvalidInput);
+    }
+
+    match &config::current().credential_store {
+        Some(value) => CredentialStoreBackend::from_str(value)
+            .map_err(ParraCliError::InvalidInput),
+        None => Ok(CredentialStoreBackend::default()),
+    }
+}
+
+pub fn open_credential_store(
+    service: &str,
+) -> Result<Box<dyn CredentialStore>, ParraCliError> {
+    match selected_backend()? {
+        #[cfg(target_os = "macos")]
+        CredentialStoreBackend::Keychain => {
+            Ok(Box::new(keychain::KeychainCredentialStore::new(service)))
+        }
+        #[cfg(not(target_os = "macos"))]
+        CredentialStoreBackend::Keychain => Err(ParraCliError::InvalidInput(
+            "The keychain credential store is only available on macOS. Set PARRA_CREDENTIAL_STORE=file to use an encrypted file instead."
+                .to_string(),
+        )),
+        CredentialStoreBackend::EncryptedFile => Ok(Box::new(
+            encrypted_file::EncryptedFileCredentialStore::new(service)?,
+        )),
+        CredentialStoreBackend::Memory => {
+            Ok(Box::new(memory::MemoryCredentialStore::new(service)))
+        }
+    }
+}
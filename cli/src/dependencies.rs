@@ -1,3 +1,4 @@
+use crate::errors::ParraCliError;
 use crate::logger::debug_println;
 use crate::types::dependency::SemanticVersion;
 use semver::{Version, VersionReq};
@@ -18,20 +19,20 @@ pub enum DerivedDependency {
 pub fn install_missing_dependencies(
     desired_xcode_version: SemanticVersion,
     desired_ios_runtime_version: SemanticVersion,
-) {
-    install_brew_dependencies();
-    install_xcode(desired_xcode_version, desired_ios_runtime_version);
+) -> Result<(), ParraCliError> {
+    install_brew_dependencies()?;
+    install_xcode(desired_xcode_version, desired_ios_runtime_version)
 }
 
 pub fn check_for_missing_dependencies(
     min_xcode_version: SemanticVersion,
     desired_ios_runtime_version: SemanticVersion,
-) -> Vec<DerivedDependency> {
+) -> Result<Vec<DerivedDependency>, ParraCliError> {
     let mut missing_deps = Vec::<DerivedDependency>::new();
 
     debug_println!("Checking for missing dependencies");
 
-    match is_xcode_version_valid(min_xcode_version) {
+    match is_xcode_version_valid(min_xcode_version)? {
         Some(is_valid) => {
             debug_println!("Xcode version is valid: {}", is_valid);
 
@@ -41,7 +42,7 @@ pub fn check_for_missing_dependencies(
             }
 
             // Regardless of Xcode being installed, check if a valid runtime is installed.
-            if !is_simulator_version_valid(desired_ios_runtime_version) {
+            if !is_simulator_version_valid(desired_ios_runtime_version)? {
                 missing_deps.push(DerivedDependency::SimulatorRuntime);
             }
         }
@@ -52,10 +53,12 @@ pub fn check_for_missing_dependencies(
         }
     }
 
-    return missing_deps;
+    return Ok(missing_deps);
 }
 
-fn is_simulator_version_valid(min_version: SemanticVersion) -> bool {
+fn is_simulator_version_valid(
+    min_version: SemanticVersion,
+) -> Result<bool, ParraCliError> {
     // xcrun simctl list devices | grep "\-\- iOS" | awk '{print $3}'
     // First command: `xcrun simctl list devices`
     let list_devices = Command::new("xcrun")
@@ -64,74 +67,68 @@ fn is_simulator_version_valid(min_version: SemanticVersion) -> bool {
         .arg("devices")
         .stdout(Stdio::piped())
         .spawn()
-        .expect("Failed to list iOS runtimes");
+        .map_err(|error| ParraCliError::external_tool("xcrun", error))?;
 
     // Second command: `grep "\-\- iOS"`
     let grep_for_ios_runtimes = Command::new("grep")
         .arg("\\-\\- iOS")
-        .stdin(Stdio::from(
-            list_devices
-                .stdout
-                .expect("Failed to get search for iOS runtime"),
-        ))
+        .stdin(Stdio::from(list_devices.stdout.ok_or_else(|| {
+            ParraCliError::external_tool("xcrun", "Failed to read output")
+        })?))
         .stdout(Stdio::piped())
         .spawn()
-        .expect("Failed to execute grep -- iOS");
+        .map_err(|error| ParraCliError::external_tool("grep", error))?;
 
     // Third command: `awk '{print $3}'`
     let format_results = Command::new("awk")
         .arg("{print $3}")
-        .stdin(Stdio::from(
-            grep_for_ios_runtimes
-                .stdout
-                .expect("Failed to format matching iOS runtimes"),
-        ))
+        .stdin(Stdio::from(grep_for_ios_runtimes.stdout.ok_or_else(
+            || ParraCliError::external_tool("grep", "Failed to read output"),
+        )?))
         .stdout(Stdio::piped())
         .spawn()
-        .expect("Failed to execute awk '{print $3}'");
+        .map_err(|error| ParraCliError::external_tool("awk", error))?;
 
     // Capture the final output
     let output = format_results
         .wait_with_output()
-        .expect("Failed to read stdout of third command");
+        .map_err(|error| ParraCliError::external_tool("awk", error))?;
 
     if !output.status.success() {
-        return false;
+        return Ok(false);
     }
 
-    return String::from_utf8_lossy(&output.stdout)
+    return Ok(String::from_utf8_lossy(&output.stdout)
         .lines()
-        .map(|line| {
+        .filter_map(|line| {
             let components: Vec<&str> = line.trim().split(".").collect();
             let [major, minor] = components[..] else {
-                panic!("Major or minor component missing")
+                debug_println!("Skipping malformed iOS runtime: {}", line);
+
+                return None;
             };
 
-            SemanticVersion {
-                major: major
-                    .parse()
-                    .expect("Major version component wasn't a valid int8"),
-                minor: minor
-                    .parse()
-                    .expect("Minor version component wasn't a valid int8"),
+            Some(SemanticVersion {
+                major: major.parse().ok()?,
+                minor: minor.parse().ok()?,
                 patch: 0,
-            }
+            })
         })
         .any(|version| {
             return version.major > min_version.major
                 || (version.major == min_version.major
                     && version.minor >= min_version.minor);
-        });
+        }));
 }
 
 // most recent non-beta xcode version
-fn select_latest_xcode_version() {
+fn select_latest_xcode_version() -> Result<(), ParraCliError> {
     debug_println!("Selecting latest Xcode version");
 
     let xcodes_output = Command::new("xcodes")
         .arg("installed")
         .output()
-        .expect("Failed to execute xcodes installed");
+        .map_err(|error| ParraCliError::external_tool("xcodes", error))?;
 
     let stdout = String::from_utf8_lossy(&xcodes_output.stdout);
 
@@ -211,11 +208,15 @@ fn select_latest_xcode_version() {
             }
         }
     }
+
+    Ok(())
 }
 
 /// Returns true if Xcode is installed and valid version, false if installed and invalid version
 /// and None if Xcode isn't installed.
-fn is_xcode_version_valid(min_version: SemanticVersion) -> Option<bool> {
+fn is_xcode_version_valid(
+    min_version: SemanticVersion,
+) -> Result<Option<bool>, ParraCliError> {
     debug_println!("Checking for valid Xcode version");
 
     let output: Output = match Command::new("xcodebuild")
@@ -226,13 +227,15 @@ fn is_xcode_version_valid(min_version: SemanticVersion) -> Option<bool> {
             if !output.status.success() {
                 debug_println!("Failed to check Xcode version (bad exit code)");
 
-                select_latest_xcode_version();
+                select_latest_xcode_version()?;
 
                 Command::new("xcodebuild")
                     .arg("-version")
                     .stderr(Stdio::null())
                     .output()
-                    .expect("Failed to get xcodebuild -version")
+                    .map_err(|error| {
+                        ParraCliError::external_tool("xcodebuild", error)
+                    })?
             } else {
                 output
             }
@@ -240,18 +243,20 @@ fn is_xcode_version_valid(min_version: SemanticVersion) -> Option<bool> {
         Err(_) => {
             debug_println!("Failed to check Xcode version");
 
-            select_latest_xcode_version();
+            select_latest_xcode_version()?;
 
             Command::new("xcodebuild")
                 .arg("-version")
                 .stderr(Stdio::null())
                 .output()
-                .expect("Failed to get xcodebuild -version")
+                .map_err(|error| {
+                    ParraCliError::external_tool("xcodebuild", error)
+                })?
         }
     };
 
     if !output.status.success() {
-        return None;
+        return Ok(None);
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -264,7 +269,7 @@ fn is_xcode_version_valid(min_version: SemanticVersion) -> Option<bool> {
     let min_version_req =
         match VersionReq::parse(&format!(">={}", min_version_string)) {
             Ok(req) => req,
-            Err(_) => return Some(false), // Return false if the minimum version string is invalid
+            Err(_) => return Ok(Some(false)), // Return false if the minimum version string is invalid
         };
 
     for line in stdout.lines() {
@@ -279,7 +284,7 @@ fn is_xcode_version_valid(min_version: SemanticVersion) -> Option<bool> {
 
                 if let Ok(version) = Version::parse(&version_str) {
                     if min_version_req.matches(&version) {
-                        return Some(true);
+                        return Ok(Some(true));
                     }
                 }
             }
@@ -288,10 +293,10 @@ fn is_xcode_version_valid(min_version: SemanticVersion) -> Option<bool> {
 
     println!("No installed Xcode version meets the minimum requirement");
 
-    return Some(false);
+    return Ok(Some(false));
 }
 
-fn install_brew_dependencies() {
+fn install_brew_dependencies() -> Result<(), ParraCliError> {
     let dependencies = vec!["xcodes", "aria2"];
 
     println!(
@@ -305,18 +310,20 @@ fn install_brew_dependencies() {
         .arg("install")
         .args(dependencies)
         .output()
-        .expect("Failed to execute command");
+        .map_err(|error| ParraCliError::external_tool("brew", error))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprintln!("Command failed. Error:\n{}", stderr);
     }
+
+    Ok(())
 }
 
 fn install_xcode(
     xcode_version: SemanticVersion,
     runtime_version: SemanticVersion,
-) {
+) -> Result<(), ParraCliError> {
     let xcode_version_string = format!(
         "{}.{}.{}",
         xcode_version.major, xcode_version.minor, xcode_version.patch
@@ -340,13 +347,13 @@ fn install_xcode(
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .output()
-        .expect("Failed to execute Xcode install command");
+        .map_err(|error| ParraCliError::external_tool("xcodes", error))?;
 
     if !xcode_install_output.status.success() {
         let stderr = String::from_utf8_lossy(&xcode_install_output.stderr);
         eprintln!("Xcode install failed. Error:\n{}", stderr);
 
-        return;
+        return Ok(());
     }
 
     println!("Successfully installed Xcode: {}", version_string_clone);
@@ -362,7 +369,7 @@ fn install_xcode(
             runtime_version_string
         ))
         .output()
-        .expect("Failed to execute command");
+        .map_err(|error| ParraCliError::external_tool("xcodes", error))?;
 
     let binding = String::from_utf8_lossy(&latest_matching_runtime.stdout);
     let runtime_to_install = binding.trim();
@@ -400,6 +407,8 @@ fn install_xcode(
         "Successfully installed iOS {} runtime",
         runtime_version_string_clone
     );
+
+    Ok(())
 }
 
 fn ensure_full_semver(version: &str) -> String {
@@ -0,0 +1,177 @@
+use inquire::InquireError;
+use std::error::Error;
+use std::fmt::Display;
+use std::io;
+use std::path::PathBuf;
+
+/// Every error the CLI can produce. Each variant maps to a distinct process
+/// exit code so that scripts can tell failures apart.
+#[derive(Debug)]
+pub enum ParraCliError {
+    /// No usable credential could be obtained, or the auth server rejected
+    /// the request.
+    Auth(String),
+    /// The server responded with a non-success status code.
+    HttpStatus {
+        url: String,
+        status: u16,
+        body: String,
+    },
+    /// The request couldn't be completed, e.g. because of a connection
+    /// failure or timeout.
+    Network(reqwest::Error),
+    /// A response or file couldn't be deserialized.
+    Deserialization(String),
+    /// A template couldn't be parsed or rendered.
+    TemplateRendering(String),
+    /// Reading from or writing to the filesystem failed.
+    Filesystem {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// An external tool, like xcodegen or git, couldn't be run or exited
+    /// unsuccessfully.
+    ExternalTool { tool: String, message: String },
+    /// Credentials couldn't be read from or written to the credential store.
+    CredentialStore(String),
+    /// A flag, config value or other user provided input was invalid.
+    InvalidInput(String),
+    /// The user declined to continue or interrupted a prompt.
+    UserCancelled(String),
+}
+
+impl ParraCliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ParraCliError::InvalidInput(_) => 2,
+            ParraCliError::Auth(_) => 3,
+            ParraCliError::HttpStatus { .. } => 4,
+            ParraCliError::Network(_) => 5,
+            ParraCliError::Deserialization(_) => 6,
+            ParraCliError::TemplateRendering(_) => 7,
+            ParraCliError::Filesystem { .. } => 8,
+            ParraCliError::ExternalTool { .. } => 9,
+            ParraCliError::CredentialStore(_) => 10,
+            // Matches the conventional exit status for SIGINT.
+            ParraCliError::UserCancelled(_) => 130,
+        }
+    }
+
+    pub fn filesystem(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        ParraCliError::Filesystem {
+            path: Some(path.into()),
+            source,
+        }
+    }
+
+    pub fn external_tool(tool: &str, message: impl Display) -> Self {
+        ParraCliError::ExternalTool {
+            tool: tool.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for ParraCliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParraCliError::Auth(message) => {
+                write!(f, "Authentication failed: {}", message)
+            }
+            ParraCliError::HttpStatus { url, status, body } => {
+                write!(
+                    f,
+                    "Request to {} failed with status {}: {}",
+                    url, status, body
+                )
+            }
+            ParraCliError::Network(error) => {
+                write!(f, "Network error: {}", error)
+            }
+            ParraCliError::Deserialization(message) => {
+                write!(f, "Failed to decode data: {}", message)
+            }
+            ParraCliError::TemplateRendering(message) => {
+                write!(f, "Template error: {}", message)
+            }
+            ParraCliError::Filesystem { path, source } => match path {
+                Some(path) => write!(f, "{}: {}", path.display(), source),
+                None => write!(f, "Filesystem error: {}", source),
+            },
+            ParraCliError::ExternalTool { tool, message } => {
+                write!(f, "{} failed: {}", tool, message)
+            }
+            ParraCliError::CredentialStore(message) => {
+                write!(f, "Credential store error: {}", message)
+            }
+            ParraCliError::InvalidInput(message) => write!(f, "{}", message),
+            ParraCliError::UserCancelled(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for ParraCliError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParraCliError::Network(error) => Some(error),
+            ParraCliError::Filesystem { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ParraCliError {
+    fn from(error: reqwest::Error) -> Self {
+        ParraCliError::Network(error)
+    }
+}
+
+impl From<serde_json::Error> for ParraCliError {
+    fn from(error: serde_json::Error) -> Self {
+        ParraCliError::Deserialization(error.to_string())
+    }
+}
+
+impl From<std::string::FromUtf8Error> for ParraCliError {
+    fn from(error: std::string::FromUtf8Error) -> Self {
+        ParraCliError::Deserialization(error.to_string())
+    }
+}
+
+impl From<io::Error> for ParraCliError {
+    fn from(error: io::Error) -> Self {
+        ParraCliError::Filesystem {
+            path: None,
+            source: error,
+        }
+    }
+}
+
+impl From<liquid::Error> for ParraCliError {
+    fn from(error: liquid::Error) -> Self {
+        ParraCliError::TemplateRendering(error.to_string())
+    }
+}
+
+impl From<git2::Error> for ParraCliError {
+    fn from(error: git2::Error) -> Self {
+        ParraCliError::external_tool("git", error.message())
+    }
+}
+
+impl From<InquireError> for ParraCliError {
+    fn from(error: InquireError) -> Self {
+        match error {
+            InquireError::OperationCanceled
+            | InquireError::OperationInterrupted => {
+                ParraCliError::UserCancelled("Cancelled by user".to_string())
+            }
+            InquireError::NotTTY => ParraCliError::InvalidInput(
+                "Input is required but the terminal is not interactive"
+                    .to_string(),
+            ),
+            InquireError::IO(error) => error.into(),
+            other => ParraCliError::InvalidInput(other.to_string()),
+        }
+    }
+}
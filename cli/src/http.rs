@@ -0,0 +1,206 @@
+use crate::config;
+use crate::constants::built::built_info;
+use crate::errors::ParraCliError;
+use crate::logger::debug_println;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{
+    Certificate, Client, Method, NoProxy, Proxy, RequestBuilder, Response,
+    StatusCode,
+};
+use std::env;
+use std::fs::read;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The total number of attempts made for a request, including the first.
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(500);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Builds the shared client from the current config. Must be called after
+/// `config::init` and before any requests are made, so that invalid proxy or
+/// CA bundle settings are reported instead of silently ignored.
+pub fn init() -> Result<(), ParraCliError> {
+    let client = build_client()?;
+
+    // Ignore the result. Only the first initialization wins.
+    let _ = CLIENT.set(client);
+
+    Ok(())
+}
+
+/// The client shared by every request the CLI makes, so that connections are
+/// reused between requests.
+pub fn client() -> &'static Client {
+    CLIENT.get_or_init(|| build_client().unwrap_or_default())
+}
+
+fn build_client() -> Result<Client, ParraCliError> {
+    let mut builder = Client::builder()
+        .user_agent(format!("parra-cli/{}", built_info::PKG_VERSION))
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT);
+
+    // reqwest would also pick these up on its own, but doing it explicitly
+    // means a malformed proxy URL is reported rather than ignored. Adding any
+    // proxy turns off reqwest's own handling of the environment, so every
+    // variable it would have used has to be covered here. The scheme specific
+    // ones come first since the first matching proxy is used.
+    for name in ["HTTPS_PROXY", "HTTP_PROXY", "ALL_PROXY"] {
+        let Some(proxy_url) = proxy_env_var(name) else {
+            continue;
+        };
+
+        let proxy = match name {
+            "HTTPS_PROXY" => Proxy::https(&proxy_url),
+            "HTTP_PROXY" => Proxy::http(&proxy_url),
+            _ => Proxy::all(&proxy_url),
+        }
+        .map_err(|error| {
+            ParraCliError::InvalidInput(format!(
+                "Invalid {} \"{}\": {}",
+                name, proxy_url, error
+            ))
+        })?;
+
+        builder = builder.proxy(proxy.no_proxy(NoProxy::from_env()));
+    }
+
+    if let Some(ca_bundle) = &config::current().ca_bundle {
+        let pem = read(ca_bundle)
+            .map_err(|error| ParraCliError::filesystem(ca_bundle, error))?;
+        let certificates =
+            Certificate::from_pem_bundle(&pem).map_err(|error| {
+                ParraCliError::InvalidInput(format!(
+                    "Invalid CA bundle at {}: {}",
+                    ca_bundle.display(),
+                    error
+                ))
+            })?;
+
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Proxy variables are conventionally accepted in either case.
+fn proxy_env_var(name: &str) -> Option<String> {
+    env::var(name)
+        .or_else(|_| env::var(name.to_lowercase()))
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Sends the request produced by `build`, retrying on connection errors, 429
+/// and 5xx responses with jittered exponential backoff. `build` is called once
+/// per attempt since a request can't be sent twice.
+///
+/// Requests that aren't idempotent are only retried when the server can't have
+/// acted on them, i.e. when the connection couldn't be established or the
+/// server responded with 429.
+pub async fn send_with_retry<F>(
+    method: &Method,
+    build: F,
+) -> Result<Response, ParraCliError>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let idempotent = is_idempotent(method);
+    let mut attempt = 1;
+
+    loop {
+        let is_last_attempt = attempt >= MAX_ATTEMPTS;
+
+        match build().send().await {
+            Ok(response) => {
+                let status = response.status();
+                let retryable = status == StatusCode::TOO_MANY_REQUESTS
+                    || (idempotent && status.is_server_error());
+
+                if !retryable || is_last_attempt {
+                    return Ok(response);
+                }
+
+                let delay = retry_after(&response)
+                    .unwrap_or_else(|| backoff_delay(attempt));
+
+                debug_println!(
+                    "Request to {} failed with status {}. Retrying in {:?}",
+                    response.url(),
+                    status,
+                    delay
+                );
+
+                tokio::time::sleep(delay).await;
+            }
+            Err(error) => {
+                let retryable = error.is_connect()
+                    || (idempotent
+                        && (error.is_timeout() || error.is_request()));
+
+                if !retryable || is_last_attempt {
+                    return Err(error.into());
+                }
+
+                let delay = backoff_delay(attempt);
+
+                debug_println!(
+                    "Request failed with error: {}. Retrying in {:?}",
+                    error,
+                    delay
+                );
+
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        attempt += 1;
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET
+            | Method::HEAD
+            | Method::PUT
+            | Method::DELETE
+            | Method::OPTIONS
+    )
+}
+
+/// Full jitter: a random delay between zero and the exponential backoff for
+/// the attempt, so that concurrent clients don't retry in lockstep.
+fn backoff_delay(attempt: u32) -> Duration {
+    let max_delay = BASE_RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt - 1))
+        .min(MAX_RETRY_DELAY);
+
+    max_delay.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    let delay = if let Ok(seconds) = value.parse::<u64>() {
+        Duration::from_secs(seconds)
+    } else {
+        let date = DateTime::parse_from_rfc2822(value).ok()?;
+
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO)
+    };
+
+    Some(delay.min(MAX_RETRY_DELAY))
+}
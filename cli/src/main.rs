@@ -1,24 +1,46 @@
-use std::error::Error;
+use std::path::PathBuf;
+use std::process::exit;
 use std::{collections::HashMap, env};
 mod api;
 mod arg_parser;
 mod auth;
 mod commands;
+mod config;
 mod constants;
+mod credential_store;
 mod dependencies;
+mod errors;
+mod http;
 mod logger;
+mod manifest;
+mod profiles;
 mod project_generator;
+mod template_cache;
+mod template_source;
 mod types;
 
 use inquire::ui::{Attributes, Color, RenderConfig, StyleSheet, Styled};
 use types::color_scheme::get_supported_parra_inquire_color_scheme;
 
-use crate::arg_parser::Command;
+use crate::arg_parser::{AuthCommand, Command, TemplateCommand};
+use crate::errors::ParraCliError;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() {
     let cli = arg_parser::parse_args();
 
+    let config_result = config::init(config::ConfigOverrides {
+        config_path: cli.global.config_path.map(PathBuf::from),
+        api_base_url: cli.global.api_base_url,
+        auth_base_url: cli.global.auth_base_url,
+        auth0_client_id: cli.global.auth0_client_id,
+        profile: cli.global.profile,
+    });
+
+    if let Err(error) = config_result.and_then(|_| http::init()) {
+        exit_with_error(&error);
+    }
+
     apply_cli_theme();
 
     let command_name = &cli.command.to_string();
@@ -35,28 +57,83 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 commands::bootstrap::execute_sample_bootstrap(
                     bootstrap_args.project_path,
                     use_local_packages,
+                    bootstrap_args.code_signing.into(),
+                    bootstrap_args.template_source,
                 )
                 .await
             } else {
                 commands::bootstrap::execute_bootstrap(
-                    bootstrap_args.application_id,
-                    bootstrap_args.tenant_id,
-                    bootstrap_args.project_path,
-                    bootstrap_args.template_name.to_string(),
+                    commands::bootstrap::BootstrapOptions {
+                        application_id: bootstrap_args.application_id,
+                        tenant_id: bootstrap_args.tenant_id,
+                        project_path: bootstrap_args.project_path,
+                        template_name: bootstrap_args.template_name,
+                        manifest_path: bootstrap_args
+                            .manifest_path
+                            .map(PathBuf::from),
+                        non_interactive: bootstrap_args.non_interactive,
+                        tenant_name: bootstrap_args.tenant_name,
+                        application_name: bootstrap_args.application_name,
+                        bundle_id: bootstrap_args.bundle_id,
+                        overwrite: bootstrap_args.overwrite,
+                        dependency_policy: bootstrap_args.dependency_policy,
+                        dry_run: bootstrap_args.dry_run,
+                        marketing_version: bootstrap_args.marketing_version,
+                        build_number: bootstrap_args.build_number,
+                        deployment_target: bootstrap_args.deployment_target,
+                        code_signing: bootstrap_args.code_signing.into(),
+                        inputs: bootstrap_args.template_inputs.inputs,
+                        inputs_path: bootstrap_args
+                            .template_inputs
+                            .inputs_path
+                            .map(PathBuf::from),
+                        template_source: bootstrap_args.template_source,
+                        ..Default::default()
+                    },
                 )
                 .await
             }
         }
-        Command::Login(_) => {
-            commands::login::execute_login().await;
-
-            Ok(())
+        Command::Render(render_args) => commands::render::execute_render(
+            PathBuf::from(render_args.template_dir),
+            PathBuf::from(render_args.context_path),
+            PathBuf::from(render_args.output_dir),
+            render_args.use_local_packages,
+            render_args.overwrite,
+            render_args.template_inputs.inputs,
+            render_args.template_inputs.inputs_path.map(PathBuf::from),
+        ),
+        Command::Template(template_args) => match template_args.command {
+            TemplateCommand::Lint(lint_args) => {
+                commands::template::execute_lint(PathBuf::from(
+                    lint_args.template_dir,
+                ))
+            }
+            TemplateCommand::List(list_args) => {
+                commands::template::execute_list(list_args.template_source)
+                    .await
+            }
+            TemplateCommand::Cache(cache_args) => {
+                commands::template::execute_cache(cache_args.command)
+            }
+        },
+        Command::Login(login_args) => {
+            commands::login::execute_login(login_args.token).await
         }
-        Command::Logout(_) => {
-            commands::logout::execute_logout();
-
-            Ok(())
+        Command::Logout(logout_args) => {
+            commands::logout::execute_logout(logout_args.all_profiles).await
+        }
+        Command::Profiles(profiles_args) => {
+            commands::profiles::execute_profiles(profiles_args.command).await
         }
+        Command::Whoami(whoami_args) => {
+            commands::whoami::execute_whoami(whoami_args.json).await
+        }
+        Command::Auth(auth_args) => match auth_args.command {
+            AuthCommand::Status(status_args) => {
+                commands::whoami::execute_whoami(status_args.json).await
+            }
+        },
     };
 
     match result {
@@ -65,8 +142,6 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 &format!("{}_succeeded", command_event_prefix),
                 None,
             );
-
-            Ok(())
         }
         Err(error) => {
             let _ = api::report_event(
@@ -74,11 +149,19 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 Some(HashMap::from([("error", error.to_string().as_str())])),
             );
 
-            Err(error)
+            exit_with_error(&error);
         }
     }
 }
 
+/// The only place the process exits with a failure status. The code depends
+/// on the kind of error so that scripts can distinguish between them.
+fn exit_with_error(error: &ParraCliError) -> ! {
+    eprintln!("Error: {}", error);
+
+    exit(error.exit_code())
+}
+
 fn apply_cli_theme() {
     let render_config = match env::var("NO_COLOR") {
         Ok(_) => RenderConfig::empty(),
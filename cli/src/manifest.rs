@@ -0,0 +1,152 @@
+use crate::errors::ParraCliError;
+use crate::project_generator::inputs;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// A declarative description of a project for `parra bootstrap --from`. Every
+/// field is optional. Anything left out is taken from flags, profile defaults
+/// or prompts exactly as if no manifest had been given.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapManifest {
+    pub tenant: Option<ManifestTenant>,
+    pub application: Option<ManifestApplication>,
+    pub template: Option<String>,
+    /// A directory, git URL or archive to load the template from.
+    pub template_source: Option<String>,
+    pub project_path: Option<String>,
+    pub marketing_version: Option<String>,
+    pub build_number: Option<String>,
+    pub deployment_target: Option<String>,
+    pub code_signing: Option<ManifestCodeSigning>,
+    /// Answers to the template's input prompts, keyed by input group and
+    /// then by input key.
+    #[serde(default)]
+    pub inputs: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+}
+
+/// Either an existing workspace by ID, or one to look up or create by name.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestTenant {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Either an existing application by ID, or one to look up or create by name.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestApplication {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    /// Only used when a new application is created.
+    pub bundle_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestCodeSigning {
+    pub team_id: Option<String>,
+    /// Applies to both configurations unless they override it.
+    pub style: Option<String>,
+    #[serde(default)]
+    pub debug: ManifestSigningConfiguration,
+    #[serde(default)]
+    pub release: ManifestSigningConfiguration,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestSigningConfiguration {
+    pub identity: Option<String>,
+    pub style: Option<String>,
+    pub profile_specifier: Option<String>,
+}
+
+impl BootstrapManifest {
+    /// The template input answers keyed by `<group>.<key>`, with every value
+    /// converted to a string.
+    pub fn input_answers(&self) -> BTreeMap<String, String> {
+        self.inputs
+            .iter()
+            .flat_map(|(group, answers)| {
+                answers.iter().map(move |(key, value)| {
+                    (format!("{}.{}", group, key), inputs::answer_string(value))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Reads a manifest, choosing the format from the file extension.
+pub fn read_manifest(
+    path: &PathBuf,
+) -> Result<BootstrapManifest, ParraCliError> {
+    let contents = read_to_string(path)
+        .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    let manifest = match extension.as_deref() {
+        Some("toml") => parse(path, toml::from_str(&contents)),
+        Some("yaml") | Some("yml") => {
+            parse(path, serde_yaml::from_str(&contents))
+        }
+        Some("json") => parse(path, serde_json::from_str(&contents)),
+        _ => Err(ParraCliError::InvalidInput(format!(
+            "Unsupported manifest {}. Use a .toml, .yaml, .yml or .json file.",
+            path.display()
+        ))),
+    }?;
+
+    validate(&manifest)?;
+
+    Ok(manifest)
+}
+
+fn parse<T: DeserializeOwned, E: std::fmt::Display>(
+    path: &Path,
+    result: Result<T, E>,
+) -> Result<T, ParraCliError> {
+    result.map_err(|error| {
+        ParraCliError::InvalidInput(format!(
+            "Invalid manifest at {}: {}",
+            path.display(),
+            error
+        ))
+    })
+}
+
+fn validate(manifest: &BootstrapManifest) -> Result<(), ParraCliError> {
+    if let Some(tenant) = &manifest.tenant {
+        if tenant.id.is_some() == tenant.name.is_some() {
+            return Err(ParraCliError::InvalidInput(
+                "The manifest's tenant must have exactly one of id or name."
+                    .to_string(),
+            ));
+        }
+    }
+
+    if let Some(application) = &manifest.application {
+        if application.id.is_some() == application.name.is_some() {
+            return Err(ParraCliError::InvalidInput(
+                "The manifest's application must have exactly one of id or name."
+                    .to_string(),
+            ));
+        }
+
+        if application.id.is_some() && application.bundle_id.is_some() {
+            return Err(ParraCliError::InvalidInput(
+                "The manifest's application bundle_id can only be used with a name."
+                    .to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
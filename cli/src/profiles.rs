@@ -0,0 +1,104 @@
+use crate::config;
+use crate::errors::ParraCliError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, read_to_string};
+use std::path::PathBuf;
+
+pub const DEFAULT_PROFILE_NAME: &str = "default";
+
+/// The on-disk representation of `profiles.toml`, which lives next to the
+/// config file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfilesFile {
+    /// The profile used when neither --profile nor PARRA_PROFILE are set.
+    pub active: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Per profile defaults. The credential for a profile is kept in the
+/// credential store rather than here.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub tenant_id: Option<String>,
+    pub application_id: Option<String>,
+}
+
+/// The credential store account for a profile. The default profile uses the
+/// bare client ID so that logins from before profiles existed keep working.
+pub fn credential_account(client_id: &str, profile: &str) -> String {
+    if profile == DEFAULT_PROFILE_NAME {
+        client_id.to_string()
+    } else {
+        format!("{}:{}", client_id, profile)
+    }
+}
+
+pub fn read_profiles_file(
+    path: &PathBuf,
+) -> Result<ProfilesFile, ParraCliError> {
+    if !path.exists() {
+        return Ok(ProfilesFile::default());
+    }
+
+    let contents = read_to_string(path)
+        .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+    toml::from_str::<ProfilesFile>(&contents).map_err(|err| {
+        ParraCliError::InvalidInput(format!(
+            "Invalid profiles file at {}: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+pub fn read_profiles() -> Result<ProfilesFile, ParraCliError> {
+    read_profiles_file(&config::current().profiles_path)
+}
+
+pub fn write_profiles(profiles: &ProfilesFile) -> Result<(), ParraCliError> {
+    let path = &config::current().profiles_path;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let contents = toml::to_string_pretty(profiles)
+        .map_err(|error| ParraCliError::InvalidInput(error.to_string()))?;
+
+    fs::write(path, contents)
+        .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+    Ok(())
+}
+
+/// The stored defaults for the profile selected for this invocation.
+pub fn current_profile() -> Result<Profile, ParraCliError> {
+    let profiles = read_profiles()?;
+
+    Ok(profiles
+        .profiles
+        .get(&config::current().profile)
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Records that a profile exists, so that it shows up in `parra profiles list`
+/// even before any defaults have been set for it.
+pub fn register_profile(name: &str) -> Result<(), ParraCliError> {
+    let mut profiles = read_profiles()?;
+
+    if !profiles.profiles.contains_key(name) {
+        profiles
+            .profiles
+            .insert(name.to_string(), Profile::default());
+
+        write_profiles(&profiles)?;
+    }
+
+    Ok(())
+}
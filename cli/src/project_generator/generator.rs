@@ -1,113 +1,95 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use std::process::{exit, Command};
-use std::{error::Error, fs, io};
+use std::process::Command;
+use std::{fs, io};
 
 use image::imageops::FilterType;
 use image::ImageFormat;
-use inquire::validator::{MaxLengthValidator, MinLengthValidator};
-use inquire::{Confirm, Text};
-use liquid::model::Value;
+use inquire::Confirm;
 use liquid::Object;
-use reqwest::get;
+use reqwest::Method;
 
-use crate::api;
-use crate::project_generator::renderer;
+use crate::errors::ParraCliError;
+use crate::project_generator::{inputs, renderer};
 use crate::types::api::Icon;
-use crate::types::templates::{CliInput, ProjectContext};
+use crate::types::templates::{ProjectContext, TemplateConfig};
+use crate::{api, http};
+
+/// The most links that are followed for a single file before it's assumed
+/// that the links form a cycle.
+const MAX_SYMLINK_DEPTH: usize = 16;
+
+/// What to do when the project directory already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistingProjectPolicy {
+    Prompt,
+    Overwrite,
+    Fail,
+}
 
+/// Generates the project. Template inputs with an entry in `input_answers`,
+/// keyed by `<group>.<key>`, aren't prompted for. When `interactive` is false,
+/// no inputs are prompted for and their defaults are used instead.
 pub async fn generate_xcode_project(
     project_dir: &PathBuf,
     template_base_dir: &PathBuf,
     template_app_dir: &PathBuf,
     template: &str,
     context: &ProjectContext,
-    prompt_for_override: bool,
-) -> Result<PathBuf, Box<dyn Error>> {
-    let mut globals = liquid::to_object(&context)?;
-
-    if let Some(input_config) = &context.config.cli_input {
-        if !input_config.inputs.is_empty() {
-            let mut liquid_map = Object::new();
-
-            for input in <Vec<CliInput> as Clone>::clone(&input_config.inputs)
-                .into_iter()
-            {
-                let mut text_input = Text::new(&input.prompt)
-                    .with_validator(MaxLengthValidator::new(input.max_length));
-
-                text_input = if input.required {
-                    text_input.with_validator(MinLengthValidator::new(
-                        input.min_length,
-                    ))
-                } else {
-                    text_input
-                };
-
-                text_input = if let Some(help_message) = &input.help_message {
-                    text_input.with_help_message(&help_message.as_str())
-                } else {
-                    text_input
-                };
-
-                text_input =
-                    if let Some(default_message) = &input.default_message {
-                        text_input.with_default(&default_message.as_str())
-                    } else {
-                        text_input
-                    };
-
-                let user_input = text_input.prompt()?;
-                let trimmed = user_input.trim().trim_matches('"');
-
-                let value = if trimmed.is_empty() {
-                    input.default.unwrap_or("".into())
-                } else {
-                    trimmed.into()
-                };
-
-                liquid_map.insert(input.key.into(), Value::scalar(value));
-            }
-
-            globals.insert(
-                <std::string::String as Clone>::clone(&input_config.name)
-                    .into(),
-                Value::Object(liquid_map),
-            );
-        }
-    }
+    existing_project_policy: ExistingProjectPolicy,
+    interactive: bool,
+    input_answers: &BTreeMap<String, String>,
+) -> Result<PathBuf, ParraCliError> {
+    let globals = template_globals(context, interactive, input_answers)?;
 
     let camel_app_name = context.app.name.upper_camel.clone();
     let target_dir = project_dir.join(camel_app_name);
 
     if project_dir.exists() {
-        if prompt_for_override {
-            let result =
-                Confirm::new("Project directory already exists. Overwrite?")
-                    .with_help_message(
-                        "If you choose not to proceed, the program will exit.",
-                    )
-                    .with_default(false)
-                    .prompt()?;
-
-            if !result {
-                exit(1);
-            } else {
-                let _ =
-                    api::report_event("cli_bootstrap_project_overridden", None);
+        match existing_project_policy {
+            ExistingProjectPolicy::Prompt => {
+                let result = Confirm::new(
+                    "Project directory already exists. Overwrite?",
+                )
+                .with_help_message(
+                    "If you choose not to proceed, the program will exit.",
+                )
+                .with_default(false)
+                .prompt()?;
+
+                if !result {
+                    return Err(ParraCliError::UserCancelled(
+                        "Not overwriting the existing project directory"
+                            .to_string(),
+                    ));
+                } else {
+                    let _ = api::report_event(
+                        "cli_bootstrap_project_overridden",
+                        None,
+                    );
+                }
+            }
+            ExistingProjectPolicy::Overwrite => {}
+            ExistingProjectPolicy::Fail => {
+                return Err(ParraCliError::InvalidInput(format!(
+                    "Project directory {} already exists. Pass --overwrite to replace it.",
+                    project_dir.display()
+                )));
             }
         }
 
         fs::remove_dir_all(&project_dir)?;
     }
 
-    copy_dir_all(template_base_dir, template_app_dir, &target_dir)?;
-
     println!("Generating project...");
 
-    renderer::render_templates_in_dir(&target_dir, &globals)?;
-
-    let rendered_project_template =
-        renderer::render_template(&template, &globals)?;
+    let rendered_project_template = render_project_files(
+        template_base_dir,
+        template_app_dir,
+        &target_dir,
+        template,
+        &globals,
+    )?;
 
     let _ = api::report_event("cli_bootstrap_template_rendered", None);
 
@@ -131,6 +113,148 @@ pub async fn generate_xcode_project(
     return Ok(target_dir);
 }
 
+/// Renders the project into `output_dir` exactly as `generate_xcode_project`
+/// would, with the rendered XcodeGen spec written to `project.yml`. Nothing is
+/// downloaded and no external tools are run. Returns the liquid globals that
+/// were used.
+pub fn render_project(
+    output_dir: &PathBuf,
+    template_base_dir: &PathBuf,
+    template_app_dir: &PathBuf,
+    template: &str,
+    context: &ProjectContext,
+    interactive: bool,
+    input_answers: &BTreeMap<String, String>,
+) -> Result<Object, ParraCliError> {
+    let globals = template_globals(context, interactive, input_answers)?;
+
+    let target_dir = output_dir.join(&context.app.name.upper_camel);
+
+    let rendered_project_template = render_project_files(
+        template_base_dir,
+        template_app_dir,
+        &target_dir,
+        template,
+        &globals,
+    )?;
+
+    let project_yaml_path = output_dir.join("project.yml");
+    fs::write(&project_yaml_path, rendered_project_template).map_err(
+        |error| ParraCliError::filesystem(&project_yaml_path, error),
+    )?;
+
+    return Ok(globals);
+}
+
+/// Copies the template's app directory to `target_dir` and renders every
+/// liquid file in it. Returns the rendered XcodeGen spec.
+fn render_project_files(
+    template_base_dir: &PathBuf,
+    template_app_dir: &PathBuf,
+    target_dir: &PathBuf,
+    template: &str,
+    globals: &Object,
+) -> Result<String, ParraCliError> {
+    copy_dir_all(template_base_dir, template_app_dir, target_dir)?;
+
+    renderer::render_templates_in_dir(target_dir, globals)?;
+
+    return renderer::render_template(template, globals);
+}
+
+/// The context as liquid globals, along with the answers to the template's
+/// inputs under their group name.
+fn template_globals(
+    context: &ProjectContext,
+    interactive: bool,
+    input_answers: &BTreeMap<String, String>,
+) -> Result<Object, ParraCliError> {
+    let mut globals = liquid::to_object(&context)?;
+
+    let inputs =
+        inputs::resolve_inputs(&context.config, interactive, input_answers)?;
+    globals.extend(inputs);
+
+    return Ok(globals);
+}
+
+/// Reads a file from a template. Templates may symlink files from other
+/// templates, with link targets relative to `templates_dir`.
+pub fn read_template_file(
+    path: &Path,
+    templates_dir: &Path,
+) -> Result<String, ParraCliError> {
+    let target = resolve_template_link(path, templates_dir)
+        .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+    return fs::read_to_string(&target)
+        .map_err(|error| ParraCliError::filesystem(&target, error));
+}
+
+/// Follows a chain of symlinks in a template to the file it ends at, with each
+/// link target relative to `templates_dir`. Paths that aren't links are
+/// returned as they are.
+pub fn resolve_template_link(
+    path: &Path,
+    templates_dir: &Path,
+) -> io::Result<PathBuf> {
+    let mut target = path.to_path_buf();
+    let mut depth = 0;
+
+    while target.is_symlink() {
+        if depth == MAX_SYMLINK_DEPTH {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Symlinks at {} form a cycle.", path.display()),
+            ));
+        }
+
+        let followed = fs::read_link(&target)?;
+        target = templates_dir.join(followed);
+        depth += 1;
+    }
+
+    return Ok(target);
+}
+
+pub fn read_template_config(
+    template_dir: &PathBuf,
+    templates_dir: &PathBuf,
+) -> Result<TemplateConfig, ParraCliError> {
+    let config_path = template_dir.join("config.json");
+    let config = read_template_file(&config_path, templates_dir)?;
+
+    return TemplateConfig::from_json(&config).map_err(|error| {
+        ParraCliError::InvalidInput(format!(
+            "Invalid template config at {}: {}",
+            config_path.display(),
+            error
+        ))
+    });
+}
+
+/// The template's XcodeGen spec, combined with the package definitions for
+/// either the local or the remote SDK packages.
+pub fn read_project_spec(
+    template_dir: &PathBuf,
+    templates_dir: &PathBuf,
+    use_local_packages: bool,
+) -> Result<String, ParraCliError> {
+    let template =
+        read_template_file(&template_dir.join("project.yml"), templates_dir)?;
+
+    let package_file = if use_local_packages {
+        "package_local.yml"
+    } else {
+        "package_remote.yml"
+    };
+
+    let package_template =
+        read_template_file(&template_dir.join(package_file), templates_dir)?;
+
+    Ok(format!("{}\n{}", template, package_template))
+}
+
 fn copy_dir_all(
     template_root: &PathBuf,
     src: &PathBuf,
@@ -149,12 +273,7 @@ fn copy_dir_all(
                 dst.as_ref().join(entry.file_name()),
             )?;
         } else {
-            let mut target = entry.path().clone();
-
-            while target.is_symlink() {
-                let followed = fs::read_link(target)?;
-                target = template_root.join(followed);
-            }
+            let target = resolve_template_link(&entry.path(), template_root)?;
 
             fs::copy(target, dst.as_ref().join(entry.file_name()))?;
         }
@@ -166,53 +285,52 @@ fn copy_dir_all(
 fn run_xcodegen(
     project_path: &PathBuf,
     template: &str,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<(), ParraCliError> {
     let tmp_project_yaml_path = Path::new("/tmp/parra_project.yml");
     fs::write(tmp_project_yaml_path, template)?;
 
-    let result = Command::new("xcodegen")
+    let output = Command::new("xcodegen")
         .arg("--spec")
-        .arg(tmp_project_yaml_path.to_str().unwrap())
+        .arg(tmp_project_yaml_path)
         .arg("--project")
-        .arg(project_path.to_str().unwrap().to_owned())
+        .arg(project_path)
         .arg("--project-root")
-        .arg(project_path.to_str().unwrap().to_owned())
-        .output();
-
-    match result {
-        Ok(output) => {
-            if output.status.success() {
-                fs::remove_file(tmp_project_yaml_path)?;
-
-                return Ok(());
-            } else {
-                let error = String::from_utf8_lossy(&output.stderr);
-                eprintln!("Error  executing command: {}", error);
-                exit(1);
-            }
-        }
-        Err(error) => {
-            eprintln!("Error generating project: {}", error);
-            exit(1);
-        }
+        .arg(project_path)
+        .output()
+        .map_err(|error| ParraCliError::external_tool("xcodegen", error))?;
+
+    if !output.status.success() {
+        return Err(ParraCliError::external_tool(
+            "xcodegen",
+            String::from_utf8_lossy(&output.stderr).trim(),
+        ));
     }
+
+    fs::remove_file(tmp_project_yaml_path)?;
+
+    return Ok(());
 }
 
 async fn replace_app_icon(
     project_path: &PathBuf,
     icon: &Icon,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<(), ParraCliError> {
     let output_path =
         project_path.join("Assets.xcassets/AppIcon.appiconset/app-icon.png");
 
     println!("Downloading app icon...");
 
     // Download the image
-    let response = get(&icon.url).await?;
+    let response =
+        http::send_with_retry(&Method::GET, || http::client().get(&icon.url))
+            .await?
+            .error_for_status()?;
     let bytes = response.bytes().await?;
 
     // Load the image
-    let image = image::load_from_memory(&bytes)?;
+    let image = image::load_from_memory(&bytes).map_err(|err| {
+        ParraCliError::Deserialization(format!("Invalid app icon: {}", err))
+    })?;
 
     let sized_image = if icon.size.width == 1024 && icon.size.height == 1024 {
         image
@@ -222,16 +340,24 @@ async fn replace_app_icon(
     };
 
     // Save the resized image to a file
-    sized_image.save_with_format(output_path, ImageFormat::Png)?;
+    sized_image
+        .save_with_format(&output_path, ImageFormat::Png)
+        .map_err(|err| match err {
+            image::ImageError::IoError(error) => {
+                ParraCliError::filesystem(&output_path, error)
+            }
+            other => ParraCliError::Deserialization(other.to_string()),
+        })?;
 
     Ok(())
 }
 
-fn install_spm_dependencies(path: &PathBuf) -> Result<(), Box<dyn Error>> {
+fn install_spm_dependencies(path: &PathBuf) -> Result<(), ParraCliError> {
     Command::new("xcodebuild")
         .arg("-resolvePackageDependencies")
         .current_dir(path)
-        .output()?;
+        .output()
+        .map_err(|error| ParraCliError::external_tool("xcodebuild", error))?;
 
     Ok(())
 }
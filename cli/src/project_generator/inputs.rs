@@ -0,0 +1,578 @@
+use crate::errors::ParraCliError;
+use crate::types::templates::{
+    qualified_input_key, CliInput, CliInputType, TemplateConfig,
+};
+use inquire::validator::Validation;
+use inquire::{Confirm, Select, Text};
+use liquid::Object;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// Shown after the options of a select input that isn't required.
+const NO_SELECTION_OPTION: &str = "(none)";
+
+/// Collects answers from `--inputs` and `--input`, with the flags taking
+/// precedence over the file. Answers are keyed by `<group>.<key>`.
+pub fn collect_answers(
+    flags: &[String],
+    answers_path: Option<&PathBuf>,
+) -> Result<BTreeMap<String, String>, ParraCliError> {
+    let mut answers = match answers_path {
+        Some(path) => read_answers_file(path)?,
+        None => BTreeMap::new(),
+    };
+
+    for flag in flags {
+        let Some((key, value)) = flag.split_once('=') else {
+            return Err(ParraCliError::InvalidInput(format!(
+                "Invalid --input \"{}\". Expected <GROUP>.<KEY>=<VALUE>.",
+                flag
+            )));
+        };
+
+        let key = key.trim();
+
+        if key.split('.').count() != 2 {
+            return Err(ParraCliError::InvalidInput(format!(
+                "Invalid --input key \"{}\". Expected <GROUP>.<KEY>.",
+                key
+            )));
+        }
+
+        answers.insert(key.to_string(), value.to_string());
+    }
+
+    return Ok(answers);
+}
+
+/// Answers files are JSON objects, keyed either by group and then by key, or
+/// by `<group>.<key>`.
+fn read_answers_file(
+    path: &PathBuf,
+) -> Result<BTreeMap<String, String>, ParraCliError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+    let invalid = |message: String| {
+        ParraCliError::InvalidInput(format!(
+            "Invalid inputs file at {}: {}",
+            path.display(),
+            message
+        ))
+    };
+
+    let entries: BTreeMap<String, Value> = serde_json::from_str(&contents)
+        .map_err(|error| invalid(error.to_string()))?;
+
+    let mut answers = BTreeMap::new();
+
+    for (name, value) in entries {
+        match value {
+            Value::Object(group) => {
+                for (key, value) in group {
+                    answers.insert(
+                        format!("{}.{}", name, key),
+                        answer_string(&value),
+                    );
+                }
+            }
+            value if name.split('.').count() == 2 => {
+                answers.insert(name, answer_string(&value));
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "\"{}\" must be either an object of answers for a group or a <GROUP>.<KEY>.",
+                    name
+                )));
+            }
+        }
+    }
+
+    return Ok(answers);
+}
+
+/// Answers from JSON are converted to strings so that they're validated in
+/// the same way as answers from flags.
+pub fn answer_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// How an input without an answer is resolved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unanswered {
+    Prompt,
+    /// Uses the default, and reports required inputs without one as missing.
+    UseDefault,
+    /// Uses the default if there is one, without reporting anything, since
+    /// the input will be prompted for later.
+    Skip,
+}
+
+/// Resolves every input in the template's input groups, as liquid objects
+/// keyed by group name. Answers are taken from `input_answers` when present,
+/// keyed by `<group>.<key>`. Otherwise they're prompted for, or the default is
+/// used when not `interactive`. Inputs whose conditions aren't met are nil.
+///
+/// Every invalid, unknown or missing answer is reported in a single error, so
+/// that they can all be fixed at once.
+pub fn resolve_inputs(
+    config: &TemplateConfig,
+    interactive: bool,
+    input_answers: &BTreeMap<String, String>,
+) -> Result<Object, ParraCliError> {
+    let unanswered = if interactive {
+        Unanswered::Prompt
+    } else {
+        Unanswered::UseDefault
+    };
+
+    return resolve_inputs_with(config, unanswered, input_answers);
+}
+
+/// Checks the answers the same way as `resolve_inputs`, but never prompts, so
+/// that problems can be reported before anything is created or asked for.
+/// Unanswered inputs are only reported as missing when not `interactive`.
+pub fn validate_answers(
+    config: &TemplateConfig,
+    interactive: bool,
+    input_answers: &BTreeMap<String, String>,
+) -> Result<(), ParraCliError> {
+    let unanswered = if interactive {
+        Unanswered::Skip
+    } else {
+        Unanswered::UseDefault
+    };
+
+    return resolve_inputs_with(config, unanswered, input_answers).map(|_| ());
+}
+
+fn resolve_inputs_with(
+    config: &TemplateConfig,
+    unanswered: Unanswered,
+    input_answers: &BTreeMap<String, String>,
+) -> Result<Object, ParraCliError> {
+    let mut resolved: BTreeMap<String, Value> = BTreeMap::new();
+    let mut groups = Object::new();
+    let mut problems = vec![];
+    let mut missing = vec![];
+
+    let known_keys: HashSet<String> = config
+        .input_groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .inputs
+                .iter()
+                .map(move |input| format!("{}.{}", group.name, input.key))
+        })
+        .collect();
+
+    for answer_key in input_answers.keys() {
+        if !known_keys.contains(answer_key) {
+            problems
+                .push(format!("The template has no input \"{}\".", answer_key));
+        }
+    }
+
+    for group in &config.input_groups {
+        if group.inputs.is_empty() {
+            continue;
+        }
+
+        let mut group_object = Object::new();
+
+        for input in &group.inputs {
+            let answer_key = format!("{}.{}", group.name, input.key);
+
+            let value = if !conditions_met(&group.name, input, &resolved) {
+                Value::Null
+            } else if let Some(answer) = input_answers.get(&answer_key) {
+                match input.parse_answer(answer) {
+                    Ok(value) => value,
+                    Err(error) => {
+                        problems.push(format!(
+                            "Invalid value for \"{}\": {}",
+                            answer_key, error
+                        ));
+
+                        Value::Null
+                    }
+                }
+            } else {
+                match unanswered {
+                    Unanswered::Prompt => prompt_for_input(input)?,
+                    Unanswered::UseDefault => {
+                        match non_interactive_input_value(&answer_key, input)? {
+                            Some(value) => value,
+                            None => {
+                                missing.push(answer_key.clone());
+
+                                Value::Null
+                            }
+                        }
+                    }
+                    Unanswered::Skip => input
+                        .default
+                        .as_ref()
+                        .and_then(|default| input.parse_value(default).ok())
+                        .unwrap_or(Value::Null),
+                }
+            };
+
+            group_object.insert(
+                input.key.clone().into(),
+                liquid::model::to_value(&value)?,
+            );
+
+            resolved.insert(answer_key, value);
+        }
+
+        groups.insert(
+            group.name.clone().into(),
+            liquid::model::Value::Object(group_object),
+        );
+    }
+
+    if !missing.is_empty() {
+        problems.push(format!(
+            "Missing required template inputs: {}. Pass them with --input <GROUP>.<KEY>=<VALUE> or --inputs <FILE>.",
+            missing.join(", ")
+        ));
+    }
+
+    if !problems.is_empty() {
+        return Err(ParraCliError::InvalidInput(problems.join("\n")));
+    }
+
+    return Ok(groups);
+}
+
+fn conditions_met(
+    group: &str,
+    input: &CliInput,
+    resolved: &BTreeMap<String, Value>,
+) -> bool {
+    input.when.iter().all(|(reference, expected)| {
+        let Some(actual) = resolved.get(&qualified_input_key(group, reference))
+        else {
+            return false;
+        };
+
+        match (actual.as_f64(), expected.as_f64()) {
+            (Some(actual), Some(expected)) => actual == expected,
+            _ => actual == expected,
+        }
+    })
+}
+
+/// The default for an input that wasn't answered, or `None` if the input is
+/// required and has no default.
+fn non_interactive_input_value(
+    answer_key: &str,
+    input: &CliInput,
+) -> Result<Option<Value>, ParraCliError> {
+    let Some(default) = &input.default else {
+        if input.required {
+            return Ok(None);
+        }
+
+        return Ok(Some(input.empty_answer()));
+    };
+
+    return input.parse_value(default).map(Some).map_err(|error| {
+        ParraCliError::InvalidInput(format!(
+            "Invalid default for the template input \"{}\": {}",
+            answer_key, error
+        ))
+    });
+}
+
+fn prompt_for_input(input: &CliInput) -> Result<Value, ParraCliError> {
+    let default = match &input.default {
+        Some(default) => input.parse_value(default).ok(),
+        None => None,
+    };
+
+    match input.input_type {
+        CliInputType::Bool => {
+            let mut confirm = Confirm::new(&input.prompt).with_default(
+                default.and_then(|value| value.as_bool()).unwrap_or(false),
+            );
+
+            if let Some(help_message) = &input.help_message {
+                confirm = confirm.with_help_message(help_message);
+            }
+
+            return Ok(Value::Bool(confirm.prompt()?));
+        }
+        CliInputType::Select => {
+            // Optional selects can be left unanswered, which is the same as
+            // not answering them when not interactive.
+            let mut options = input.options.clone();
+            if !input.required {
+                options.push(NO_SELECTION_OPTION.to_string());
+            }
+
+            let starting_cursor = default
+                .as_ref()
+                .and_then(|value| value.as_str())
+                .and_then(|value| {
+                    input.options.iter().position(|option| option == value)
+                })
+                .unwrap_or(if input.required {
+                    0
+                } else {
+                    input.options.len()
+                });
+
+            let mut select = Select::new(&input.prompt, options)
+                .with_starting_cursor(starting_cursor);
+
+            if let Some(help_message) = &input.help_message {
+                select = select.with_help_message(help_message);
+            }
+
+            let selected = select.raw_prompt()?;
+
+            if selected.index == input.options.len() {
+                return Ok(input.empty_answer());
+            }
+
+            return Ok(Value::String(selected.value));
+        }
+        CliInputType::Text
+        | CliInputType::Number
+        | CliInputType::Url
+        | CliInputType::Color => {}
+    }
+
+    let validated_input = input.clone();
+    let has_default = default.is_some();
+
+    let mut text_input =
+        Text::new(&input.prompt).with_validator(move |answer: &str| {
+            if has_default && answer.trim().is_empty() {
+                return Ok(Validation::Valid);
+            }
+
+            match validated_input.parse_answer(answer.trim().trim_matches('"'))
+            {
+                Ok(_) => Ok(Validation::Valid),
+                Err(message) => Ok(Validation::Invalid(message.into())),
+            }
+        });
+
+    if let Some(help_message) = &input.help_message {
+        text_input = text_input.with_help_message(help_message);
+    }
+
+    if let Some(default_message) = &input.default_message {
+        text_input = text_input.with_default(default_message);
+    }
+
+    let user_input = text_input.prompt()?;
+    let trimmed = user_input.trim().trim_matches('"');
+
+    if trimmed.is_empty() {
+        if let Some(default) = default {
+            return Ok(default);
+        }
+    }
+
+    return input
+        .parse_answer(trimmed)
+        .map_err(ParraCliError::InvalidInput);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template_source::TempDir;
+    use serde_json::json;
+    use std::env;
+
+    fn answers_file(dir: &TempDir, contents: &str) -> PathBuf {
+        let path = dir.path().join("answers.json");
+        fs::write(&path, contents).unwrap();
+
+        return path;
+    }
+
+    fn temp_dir() -> TempDir {
+        return TempDir::create_in(&env::temp_dir(), "parra-inputs-test")
+            .unwrap();
+    }
+
+    fn invalid_input_message(error: ParraCliError) -> String {
+        match error {
+            ParraCliError::InvalidInput(message) => message,
+            other => panic!("Expected an invalid input error: {}", other),
+        }
+    }
+
+    fn config() -> TemplateConfig {
+        return TemplateConfig::from_json(
+            &json!({
+                "schema_version": 3,
+                "input_groups": [
+                    {
+                        "name": "shop",
+                        "inputs": [
+                            { "key": "enabled", "prompt": "?", "type": "bool" },
+                            {
+                                "key": "domain",
+                                "prompt": "?",
+                                "when": { "enabled": true },
+                            },
+                            {
+                                "key": "currency",
+                                "prompt": "?",
+                                "type": "select",
+                                "options": ["usd", "eur"],
+                                "required": false,
+                            },
+                        ],
+                    },
+                ],
+            })
+            .to_string(),
+        )
+        .unwrap();
+    }
+
+    fn answers(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        return entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+    }
+
+    #[test]
+    fn flags_take_precedence_over_the_answers_file() {
+        let dir = temp_dir();
+        let path = answers_file(
+            &dir,
+            r#"{ "shop": { "domain": "a.com", "enabled": true } }"#,
+        );
+
+        let answers =
+            collect_answers(&["shop.domain=b.com=c".to_string()], Some(&path))
+                .unwrap();
+
+        assert_eq!(answers["shop.domain"], "b.com=c");
+        assert_eq!(answers["shop.enabled"], "true");
+    }
+
+    #[test]
+    fn rejects_malformed_flags() {
+        let error = collect_answers(&["shop.domain".to_string()], None)
+            .expect_err("a flag without a value should be rejected");
+
+        assert!(invalid_input_message(error).starts_with("Invalid --input \""));
+
+        let error = collect_answers(&["domain=a.com".to_string()], None)
+            .expect_err("a key without a group should be rejected");
+
+        assert!(invalid_input_message(error)
+            .starts_with("Invalid --input key \"domain\""));
+    }
+
+    #[test]
+    fn reads_grouped_and_qualified_answers() {
+        let dir = temp_dir();
+        let path = answers_file(
+            &dir,
+            r#"{ "shop": { "enabled": false, "count": 2 }, "theme.color": null }"#,
+        );
+
+        let read = read_answers_file(&path).unwrap();
+
+        assert_eq!(
+            read,
+            answers(&[
+                ("shop.count", "2"),
+                ("shop.enabled", "false"),
+                ("theme.color", ""),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_answers_files() {
+        let dir = temp_dir();
+
+        let path = answers_file(&dir, r#"{ "domain": "a.com" }"#);
+        let message = invalid_input_message(
+            read_answers_file(&path).expect_err("unqualified keys"),
+        );
+
+        assert!(message.contains("\"domain\" must be either"), "{}", message);
+
+        let path = answers_file(&dir, "[1, 2]");
+        let message = invalid_input_message(
+            read_answers_file(&path).expect_err("not an object"),
+        );
+
+        assert!(message.starts_with("Invalid inputs file at"), "{}", message);
+
+        let missing = dir.path().join("missing.json");
+
+        assert!(matches!(
+            read_answers_file(&missing),
+            Err(ParraCliError::Filesystem { .. })
+        ));
+    }
+
+    #[test]
+    fn validates_answers_without_prompting() {
+        let config = config();
+
+        let result = validate_answers(
+            &config,
+            true,
+            &answers(&[("shop.enabled", "maybe"), ("shop.size", "1")]),
+        );
+        let message = invalid_input_message(result.unwrap_err());
+
+        assert!(message.contains("The template has no input \"shop.size\"."));
+        assert!(message.contains("Invalid value for \"shop.enabled\""));
+
+        // Interactive bootstraps prompt for the rest later.
+        assert!(validate_answers(&config, true, &BTreeMap::new()).is_ok());
+    }
+
+    #[test]
+    fn reports_missing_answers_when_not_interactive() {
+        let config = config();
+
+        let message = invalid_input_message(
+            validate_answers(
+                &config,
+                false,
+                &answers(&[("shop.enabled", "y")]),
+            )
+            .unwrap_err(),
+        );
+
+        assert!(
+            message
+                .starts_with("Missing required template inputs: shop.domain."),
+            "{}",
+            message
+        );
+
+        // Inputs whose conditions aren't met aren't required.
+        assert!(validate_answers(
+            &config,
+            false,
+            &answers(&[("shop.enabled", "n")])
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,656 @@
+use crate::errors::ParraCliError;
+use crate::project_generator::{generator, renderer};
+use crate::types::templates::{
+    AppContextInfo, AppEntitlementInfo, AppEntitlementSchemes, AppNameInfo,
+    CodeSigningConfig, CodeSigningConfigs, ProjectContext, SdkContextInfo,
+    TemplateConfig, TemplateInfo, TenantContextInfo,
+    TEMPLATE_CONFIG_SCHEMA_VERSION,
+};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Words that can appear in tags without referring to a variable.
+const LIQUID_KEYWORDS: &[&str] = &[
+    "and", "or", "contains", "in", "reversed", "limit", "offset", "cols",
+    "with", "as", "true", "false", "nil", "null", "empty", "blank",
+];
+
+/// Variables that liquid defines inside of loops.
+const LOOP_VARIABLES: &[&str] = &["forloop", "tablerowloop"];
+
+/// The files outside of the App directory that are rendered.
+const PROJECT_SPEC_FILES: &[&str] =
+    &["project.yml", "package_local.yml", "package_remote.yml"];
+
+#[derive(Debug)]
+pub struct LintIssue {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => {
+                write!(f, "{}:{}: {}", self.path.display(), line, self.message)
+            }
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+/// Checks a template for everything that would otherwise only fail once
+/// someone bootstraps a project with it. Returns every problem found, sorted
+/// by file.
+pub fn lint_template(
+    template_dir: &Path,
+) -> Result<Vec<LintIssue>, ParraCliError> {
+    if !template_dir.is_dir() {
+        return Err(ParraCliError::InvalidInput(format!(
+            "{} is not a directory.",
+            template_dir.display()
+        )));
+    }
+
+    // Symlinks in templates are relative to the directory containing all of
+    // the templates.
+    let templates_dir = template_dir
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| template_dir.to_path_buf());
+
+    let mut issues = vec![];
+    let broken_links = lint_symlinks(template_dir, &templates_dir, &mut issues);
+
+    let config = lint_config(template_dir, &templates_dir, &mut issues);
+    let globals = sample_globals(config);
+    let parser = renderer::parser()?;
+
+    let mut liquid_files = vec![];
+
+    for file_name in PROJECT_SPEC_FILES {
+        let path = template_dir.join(file_name);
+
+        if path.exists() || path.is_symlink() {
+            liquid_files.push(path);
+        } else if *file_name != "package_local.yml" {
+            issues.push(LintIssue {
+                path,
+                line: None,
+                message: "Missing file.".to_string(),
+            });
+        }
+    }
+
+    let app_dir = template_dir.join("App");
+
+    if app_dir.is_dir() {
+        for entry in WalkDir::new(&app_dir).sort_by_file_name() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    issues.push(LintIssue {
+                        path: error
+                            .path()
+                            .map(Path::to_path_buf)
+                            .unwrap_or_else(|| app_dir.clone()),
+                        line: None,
+                        message: error.to_string(),
+                    });
+
+                    continue;
+                }
+            };
+
+            if !entry.file_type().is_dir()
+                && entry.file_name().to_string_lossy().contains(".liquid")
+            {
+                liquid_files.push(entry.into_path());
+            }
+        }
+    } else {
+        issues.push(LintIssue {
+            path: app_dir,
+            line: None,
+            message: "Missing App directory.".to_string(),
+        });
+    }
+
+    for path in liquid_files {
+        if broken_links.contains(&path) {
+            continue;
+        }
+
+        match generator::read_template_file(&path, &templates_dir) {
+            Ok(source) => {
+                lint_source(&path, &source, &parser, &globals, &mut issues)
+            }
+            Err(error) => issues.push(LintIssue {
+                path,
+                line: None,
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    issues.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+
+    return Ok(issues);
+}
+
+/// Checks that every symlink resolves to a file in the same way that
+/// bootstrap resolves them. Returns the links that don't.
+fn lint_symlinks(
+    template_dir: &Path,
+    templates_dir: &Path,
+    issues: &mut Vec<LintIssue>,
+) -> HashSet<PathBuf> {
+    let mut broken_links = HashSet::new();
+
+    for entry in WalkDir::new(template_dir).sort_by_file_name() {
+        let Ok(entry) = entry else {
+            // Reported when the App directory is walked.
+            continue;
+        };
+
+        if !entry.path_is_symlink() {
+            continue;
+        }
+
+        if let Some(message) = check_symlink(entry.path(), templates_dir) {
+            broken_links.insert(entry.path().to_path_buf());
+
+            issues.push(LintIssue {
+                path: entry.into_path(),
+                line: None,
+                message,
+            });
+        }
+    }
+
+    return broken_links;
+}
+
+fn check_symlink(path: &Path, templates_dir: &Path) -> Option<String> {
+    // Links further along the chain are checked along with the template
+    // they're in.
+    let followed = match fs::read_link(path) {
+        Ok(followed) => followed,
+        Err(error) => return Some(format!("Couldn't read symlink: {}", error)),
+    };
+
+    if followed.is_absolute() {
+        return Some(format!(
+            "Links to the absolute path {}. Links must be relative to {}.",
+            followed.display(),
+            templates_dir.display()
+        ));
+    }
+
+    let target = match generator::resolve_template_link(path, templates_dir) {
+        Ok(target) => target,
+        Err(error) => {
+            return Some(format!("Couldn't resolve symlink: {}", error));
+        }
+    };
+
+    if !target.exists() {
+        return Some(format!(
+            "Links to {}, which doesn't exist. Links are relative to {}.",
+            target.display(),
+            templates_dir.display()
+        ));
+    }
+
+    if target.is_dir() {
+        return Some(format!(
+            "Links to the directory {}. Only files can be linked.",
+            target.display()
+        ));
+    }
+
+    return None;
+}
+
+fn lint_config(
+    template_dir: &Path,
+    templates_dir: &Path,
+    issues: &mut Vec<LintIssue>,
+) -> Option<TemplateConfig> {
+    let path = template_dir.join("config.json");
+
+    if !path.exists() && !path.is_symlink() {
+        issues.push(LintIssue {
+            path,
+            line: None,
+            message: "Missing file.".to_string(),
+        });
+
+        return None;
+    }
+
+    // Broken links have already been reported.
+    let contents = generator::read_template_file(&path, templates_dir).ok()?;
+
+    match TemplateConfig::from_json(&contents) {
+        Ok(config) => Some(config),
+        Err(error) => {
+            issues.push(LintIssue {
+                path,
+                line: None,
+                message: format!("Invalid template config: {}", error),
+            });
+
+            None
+        }
+    }
+}
+
+fn lint_source(
+    path: &Path,
+    source: &str,
+    parser: &liquid::Parser,
+    globals: &Value,
+    issues: &mut Vec<LintIssue>,
+) {
+    if let Err(error) = parser.parse(source) {
+        let (line, message) = summarize_parse_error(&error.to_string());
+
+        issues.push(LintIssue {
+            path: path.to_path_buf(),
+            line,
+            message,
+        });
+    }
+
+    let tags = find_tags(source);
+    let locals = local_variables(&tags);
+    let mut reported = HashSet::new();
+
+    for tag in &tags {
+        for variable in tag_variables(tag) {
+            let segments = path_segments(&variable);
+
+            if locals.contains(&segments[0]) {
+                continue;
+            }
+
+            let Some(unknown_index) = find_unknown_segment(globals, &segments)
+            else {
+                continue;
+            };
+
+            let unknown = segments[..=unknown_index].join(".");
+
+            if reported.insert(unknown.clone()) {
+                issues.push(LintIssue {
+                    path: path.to_path_buf(),
+                    line: Some(tag.line),
+                    message: format!(
+                        "Unknown variable `{}`. It isn't part of the project context or the template's inputs.",
+                        unknown
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Liquid's parse errors include the position of the error and a
+/// description of it on separate lines, along with a snippet of the source.
+fn summarize_parse_error(message: &str) -> (Option<usize>, String) {
+    let position = Regex::new(r"-->\s*(\d+):(\d+)").unwrap();
+
+    let line = position
+        .captures(message)
+        .and_then(|captures| captures[1].parse::<usize>().ok());
+
+    let details: Vec<&str> = message
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("= ") || line.starts_with("requested"))
+        .map(|line| line.trim_start_matches("= "))
+        .collect();
+
+    let summary = if details.is_empty() {
+        message.lines().next().unwrap_or_default().to_string()
+    } else {
+        details.join(" ")
+    };
+
+    return (line, format!("Syntax error: {}", summary));
+}
+
+struct Tag {
+    line: usize,
+    /// The first word of a `{% %}` tag, or `None` for output tags.
+    name: Option<String>,
+    body: String,
+}
+
+/// Every output and logic tag in the source, excluding the contents of raw
+/// and comment blocks.
+fn find_tags(source: &str) -> Vec<Tag> {
+    let tag_regex =
+        Regex::new(r"(?s)\{\{-?(.*?)-?\}\}|\{%-?(.*?)-?%\}").unwrap();
+
+    let mut tags = vec![];
+    let mut skip_until: Option<&str> = None;
+
+    for captures in tag_regex.captures_iter(source) {
+        let start = captures.get(0).map(|m| m.start()).unwrap_or_default();
+        let line = source[..start].matches('\n').count() + 1;
+
+        if let Some(output) = captures.get(1) {
+            if skip_until.is_none() {
+                tags.push(Tag {
+                    line,
+                    name: None,
+                    body: output.as_str().trim().to_string(),
+                });
+            }
+
+            continue;
+        }
+
+        let Some(logic) = captures.get(2) else {
+            continue;
+        };
+
+        let logic = logic.as_str().trim();
+        let (name, body) =
+            logic.split_once(char::is_whitespace).unwrap_or((logic, ""));
+
+        if let Some(end) = skip_until {
+            if name == end {
+                skip_until = None;
+            }
+
+            continue;
+        }
+
+        match name {
+            "raw" => skip_until = Some("endraw"),
+            "comment" => skip_until = Some("endcomment"),
+            _ => tags.push(Tag {
+                line,
+                name: Some(name.to_string()),
+                body: body.trim().to_string(),
+            }),
+        }
+    }
+
+    return tags;
+}
+
+/// The variables that the template defines itself.
+fn local_variables(tags: &[Tag]) -> HashSet<String> {
+    let mut locals: HashSet<String> =
+        LOOP_VARIABLES.iter().map(|name| name.to_string()).collect();
+
+    for tag in tags {
+        let Some(name) = &tag.name else {
+            continue;
+        };
+
+        match name.as_str() {
+            "assign" | "capture" | "for" | "tablerow" | "increment"
+            | "decrement" => {
+                let variable = tag
+                    .body
+                    .split(|c: char| c.is_whitespace() || c == '=')
+                    .next()
+                    .unwrap_or_default();
+
+                if !variable.is_empty() {
+                    locals.insert(variable.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    return locals;
+}
+
+/// The variables referenced by a tag, e.g. `app.name.kebab`.
+fn tag_variables(tag: &Tag) -> Vec<String> {
+    let expression = match tag.name.as_deref() {
+        None | Some("echo") => tag.body.as_str(),
+        Some("assign") => tag
+            .body
+            .split_once('=')
+            .map(|(_, value)| value)
+            .unwrap_or_default(),
+        Some("for") | Some("tablerow") => tag
+            .body
+            .split_once(" in ")
+            .map(|(_, collection)| collection)
+            .unwrap_or_default(),
+        Some("if") | Some("elsif") | Some("unless") | Some("case")
+        | Some("when") | Some("cycle") => tag.body.as_str(),
+        _ => "",
+    };
+
+    return expression_variables(expression);
+}
+
+fn expression_variables(expression: &str) -> Vec<String> {
+    let string_regex = Regex::new(r#""[^"]*"|'[^']*'"#).unwrap();
+    let filter_regex = Regex::new(r"^\s*[\w-]+\s*:?").unwrap();
+    let named_argument_regex = Regex::new(r"[A-Za-z_][\w-]*\s*:").unwrap();
+    let variable_regex = Regex::new(
+        r"(^|[^\w.\]-])([A-Za-z_][\w-]*(?:\.[A-Za-z_][\w-]*|\[\d+\])*)",
+    )
+    .unwrap();
+
+    let without_strings = string_regex.replace_all(expression, " ");
+    let mut parts = without_strings.split('|');
+
+    let mut scanned = parts.next().unwrap_or_default().to_string();
+
+    for filter in parts {
+        let arguments = filter_regex.replace(filter, "");
+        let arguments = named_argument_regex.replace_all(&arguments, " ");
+
+        scanned.push(' ');
+        scanned.push_str(&arguments);
+    }
+
+    return variable_regex
+        .captures_iter(&scanned)
+        .map(|captures| captures[2].to_string())
+        .filter(|variable| {
+            let root = path_segments(variable).remove(0);
+
+            !LIQUID_KEYWORDS.contains(&root.as_str())
+        })
+        .collect();
+}
+
+fn path_segments(variable: &str) -> Vec<String> {
+    return variable
+        .replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .map(str::to_string)
+        .collect();
+}
+
+/// The index of the first segment of the path that doesn't exist in the
+/// globals. Optional values and maps can't be checked any further than their
+/// own name, so anything below them is accepted.
+fn find_unknown_segment(globals: &Value, segments: &[String]) -> Option<usize> {
+    let mut node = globals;
+
+    for (index, segment) in segments.iter().enumerate() {
+        let is_size_property =
+            index > 0 && ["size", "first", "last"].contains(&segment.as_str());
+
+        node = match node {
+            Value::Null => return None,
+            Value::Object(map) if map.is_empty() => return None,
+            Value::Object(map) => match map.get(segment) {
+                Some(child) => child,
+                None if is_size_property => return None,
+                None => return Some(index),
+            },
+            Value::Array(items) => {
+                if segment.parse::<usize>().is_ok()
+                    || segment == "first"
+                    || segment == "last"
+                {
+                    match items.first() {
+                        Some(item) => item,
+                        None => return None,
+                    }
+                } else if segment == "size" {
+                    return None;
+                } else {
+                    return Some(index);
+                }
+            }
+            Value::String(_) if is_size_property => return None,
+            _ => return Some(index),
+        };
+    }
+
+    return None;
+}
+
+/// Globals with the same shape as the ones that templates are rendered with.
+/// Values are placeholders, since only the names are checked.
+fn sample_globals(config: Option<TemplateConfig>) -> Value {
+    let config = config.unwrap_or(TemplateConfig {
+        schema_version: TEMPLATE_CONFIG_SCHEMA_VERSION,
+        display_name: None,
+        description: None,
+        required_tabs: vec![],
+        min_sdk_version: None,
+        min_deployment_target: None,
+        input_groups: vec![],
+    });
+
+    let entitlements = AppEntitlementInfo {
+        aps_environment: String::new(),
+        associated_domains: String::new(),
+    };
+
+    let code_sign = CodeSigningConfig {
+        identity: String::new(),
+        required: String::new(),
+        allowed: String::new(),
+        style: String::new(),
+        profile_specifier: String::new(),
+    };
+
+    let context = ProjectContext {
+        app: AppContextInfo {
+            id: String::new(),
+            build_number: String::new(),
+            marketing_version: String::new(),
+            name: AppNameInfo {
+                raw: String::new(),
+                kebab: String::new(),
+                upper_camel: String::new(),
+                display_name: String::new(),
+            },
+            bundle_id: String::new(),
+            deployment_target: String::new(),
+            entitlements: AppEntitlementSchemes {
+                debug: entitlements.clone(),
+                release: entitlements,
+            },
+            code_sign: CodeSigningConfigs {
+                debug: code_sign.clone(),
+                release: code_sign,
+            },
+            team_id: String::new(),
+            icon: None,
+        },
+        tenant: TenantContextInfo {
+            id: String::new(),
+            name: String::new(),
+        },
+        sdk: SdkContextInfo {
+            version: String::new(),
+        },
+        template: TemplateInfo::with_bootstrap_response("", None),
+        config,
+    };
+
+    let mut globals = serde_json::to_value(&context).unwrap_or_default();
+
+    let Value::Object(globals_map) = &mut globals else {
+        return globals;
+    };
+
+    for input_config in &context.config.input_groups {
+        let inputs: serde_json::Map<String, Value> = input_config
+            .inputs
+            .iter()
+            .map(|input| (input.key.clone(), Value::String(String::new())))
+            .collect();
+
+        // Like when rendering, groups without inputs aren't defined at all.
+        if !inputs.is_empty() {
+            globals_map
+                .insert(input_config.name.clone(), Value::Object(inputs));
+        }
+    }
+
+    return globals;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_generator::renderer;
+
+    /// Liquid's parse errors are only available as text, so this pins the
+    /// format that `summarize_parse_error` relies on.
+    fn parse_error(source: &str) -> String {
+        return renderer::parser()
+            .unwrap()
+            .parse(source)
+            .err()
+            .expect("the source should fail to parse")
+            .to_string();
+    }
+
+    #[test]
+    fn summarizes_unclosed_block_at_end_of_source() {
+        let error = parse_error("first\nsecond\n{% if app.name %}\nno end\n");
+
+        let (line, message) = summarize_parse_error(&error);
+
+        assert_eq!(line, Some(5), "unexpected position in: {}", error);
+        assert_eq!(
+            message,
+            "Syntax error: Unclosed block. {% endif %} tag expected."
+        );
+    }
+
+    #[test]
+    fn summarizes_error_on_later_line() {
+        let error = parse_error("first\nsecond\n{{ app.name | }}\n");
+
+        let (line, _) = summarize_parse_error(&error);
+
+        assert_eq!(line, Some(3), "unexpected position in: {}", error);
+    }
+
+    #[test]
+    fn summarizes_unknown_format() {
+        let (line, message) = summarize_parse_error("something broke\nmore");
+
+        assert_eq!(line, None);
+        assert_eq!(message, "Syntax error: something broke");
+    }
+}
@@ -1,2 +1,4 @@
 pub mod generator;
+pub mod inputs;
+pub mod lint;
 pub mod renderer;
@@ -1,5 +1,5 @@
+use crate::errors::ParraCliError;
 use std::{
-    error::Error,
     fs::{self, read_to_string},
     path::{Path, PathBuf},
 };
@@ -12,17 +12,28 @@ use walkdir::WalkDir;
 pub fn render_templates_in_dir(
     target_dir: &PathBuf,
     globals: &liquid::Object,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<(), ParraCliError> {
     for entry in WalkDir::new(target_dir) {
         match entry {
             Ok(entry) => {
                 if entry.file_type().is_file()
-                    && entry.file_name().to_str().unwrap().contains(".liquid")
+                    && entry.file_name().to_string_lossy().contains(".liquid")
                 {
                     let template = read_to_string(entry.path())?;
 
-                    let rendered_template =
-                        render_template(&template, &globals)?;
+                    let rendered_template = render_template(
+                        &template, &globals,
+                    )
+                    .map_err(|err| match err {
+                        ParraCliError::TemplateRendering(message) => {
+                            ParraCliError::TemplateRendering(format!(
+                                "{}: {}",
+                                entry.path().display(),
+                                message
+                            ))
+                        }
+                        other => other,
+                    })?;
 
                     let output_path = remove_liquid_extension(entry.path())?;
 
@@ -40,27 +51,32 @@ pub fn render_templates_in_dir(
     return Ok(());
 }
 
+/// The parser used for every template, so that linting accepts exactly what
+/// rendering does.
+pub fn parser() -> Result<liquid::Parser, ParraCliError> {
+    return Ok(liquid::ParserBuilder::with_stdlib().build()?);
+}
+
 pub fn render_template(
     template: &str,
     globals: &liquid::Object,
-) -> Result<String, Box<dyn Error>> {
-    let template = liquid::ParserBuilder::with_stdlib()
-        .build()
-        .unwrap()
-        .parse(&template)
-        .unwrap();
+) -> Result<String, ParraCliError> {
+    let template = parser()?.parse(&template)?;
 
     let result = template.render(&globals)?;
 
     Ok(result)
 }
 
-fn remove_liquid_extension(path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+fn remove_liquid_extension(path: &Path) -> Result<PathBuf, ParraCliError> {
     if let Some(str_path) = path.to_str() {
         let without_liquid = str_path.replace(".liquid", "");
 
         return Ok(PathBuf::from(without_liquid));
     }
 
-    return Err("Failed to remove suffix".into());
+    return Err(ParraCliError::TemplateRendering(format!(
+        "Failed to remove suffix from {}",
+        path.display()
+    )));
 }
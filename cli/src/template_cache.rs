@@ -0,0 +1,256 @@
+use crate::errors::ParraCliError;
+use crate::template_source::TemplateSource;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const METADATA_FILE_NAME: &str = "metadata.json";
+const CONTENT_DIR_NAME: &str = "content";
+
+/// Downloads are staged in directories in the cache directory whose names
+/// start with this prefix. They belong to fetches that are in progress.
+pub const STAGING_DIR_PREFIX: &str = ".parra-templates";
+
+/// Describes a cache entry. It's written after the content, so an entry
+/// without metadata is one that was never completely stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheMetadata {
+    pub source: String,
+    /// The commit that was fetched, for git sources.
+    pub commit: Option<String>,
+    /// RFC 3339.
+    pub fetched_at: String,
+    /// A SHA-256 of every path and file in the content directory, used to
+    /// detect cached templates that have been modified or partially deleted.
+    pub digest: String,
+}
+
+/// Templates downloaded from a remote source, keyed by the source and the
+/// reference that was requested.
+#[derive(Debug, Clone)]
+pub struct CachedTemplates {
+    pub dir: PathBuf,
+    pub metadata: CacheMetadata,
+}
+
+impl CachedTemplates {
+    pub fn content_dir(&self) -> PathBuf {
+        return self.dir.join(CONTENT_DIR_NAME);
+    }
+
+    pub fn fetched_at(&self) -> Option<DateTime<Utc>> {
+        return DateTime::parse_from_rfc3339(&self.metadata.fetched_at)
+            .ok()
+            .map(|date| date.with_timezone(&Utc));
+    }
+
+    fn is_intact(&self) -> bool {
+        return digest_dir(&self.content_dir())
+            .is_ok_and(|digest| digest == self.metadata.digest);
+    }
+}
+
+pub fn cache_dir() -> PathBuf {
+    return dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("parra")
+        .join("templates");
+}
+
+/// The cached templates for the source, if there are any and they haven't
+/// been modified since they were stored. Modified entries are removed.
+pub fn find(source: &TemplateSource) -> Option<CachedTemplates> {
+    let cached = read_entry(&cache_dir().join(cache_key(source)))?;
+
+    if cached.metadata.source != source.to_string() {
+        return None;
+    }
+
+    if !cached.is_intact() {
+        eprintln!(
+            "The cached templates for {} have been modified and will be downloaded again.",
+            source
+        );
+
+        let _ = fs::remove_dir_all(&cached.dir);
+
+        return None;
+    }
+
+    return Some(cached);
+}
+
+/// Moves `content_dir` into the cache, replacing any existing entry for the
+/// source. `content_dir` must be on the same filesystem as the cache, e.g. in
+/// a directory created in `cache_dir()`.
+pub fn store(
+    source: &TemplateSource,
+    commit: Option<String>,
+    content_dir: &Path,
+) -> Result<CachedTemplates, ParraCliError> {
+    let entry_dir = cache_dir().join(cache_key(source));
+
+    let metadata = CacheMetadata {
+        source: source.to_string(),
+        commit,
+        fetched_at: Utc::now().to_rfc3339(),
+        digest: digest_dir(content_dir)?,
+    };
+
+    if entry_dir.exists() {
+        fs::remove_dir_all(&entry_dir)
+            .map_err(|error| ParraCliError::filesystem(&entry_dir, error))?;
+    }
+
+    fs::create_dir_all(&entry_dir)
+        .map_err(|error| ParraCliError::filesystem(&entry_dir, error))?;
+
+    let cached_content_dir = entry_dir.join(CONTENT_DIR_NAME);
+
+    fs::rename(content_dir, &cached_content_dir).map_err(|error| {
+        ParraCliError::filesystem(&cached_content_dir, error)
+    })?;
+
+    let metadata_path = entry_dir.join(METADATA_FILE_NAME);
+
+    fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)
+        .map_err(|error| ParraCliError::filesystem(&metadata_path, error))?;
+
+    return Ok(CachedTemplates {
+        dir: entry_dir,
+        metadata,
+    });
+}
+
+/// Every complete cache entry, along with whether it's intact.
+pub fn list() -> Result<Vec<(CachedTemplates, bool)>, ParraCliError> {
+    let cache_dir = cache_dir();
+
+    if !cache_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let entries = fs::read_dir(&cache_dir)
+        .map_err(|error| ParraCliError::filesystem(&cache_dir, error))?;
+
+    let mut cached: Vec<(CachedTemplates, bool)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| read_entry(&entry.path()))
+        .map(|entry| {
+            let intact = entry.is_intact();
+
+            (entry, intact)
+        })
+        .collect();
+
+    cached.sort_by(|(a, _), (b, _)| a.metadata.source.cmp(&b.metadata.source));
+
+    return Ok(cached);
+}
+
+/// Removes every cached template, returning the number of entries removed.
+/// Staging directories are left alone, since another invocation may be about
+/// to move them into the cache.
+pub fn clear() -> Result<usize, ParraCliError> {
+    let cache_dir = cache_dir();
+
+    if !cache_dir.exists() {
+        return Ok(0);
+    }
+
+    let entries = fs::read_dir(&cache_dir)
+        .map_err(|error| ParraCliError::filesystem(&cache_dir, error))?;
+
+    let mut count = 0;
+
+    for entry in entries {
+        let entry = entry
+            .map_err(|error| ParraCliError::filesystem(&cache_dir, error))?;
+        let path = entry.path();
+
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(STAGING_DIR_PREFIX)
+        {
+            continue;
+        }
+
+        if read_entry(&path).is_some() {
+            count += 1;
+        }
+
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+
+        removed.map_err(|error| ParraCliError::filesystem(&path, error))?;
+    }
+
+    return Ok(count);
+}
+
+fn read_entry(dir: &Path) -> Option<CachedTemplates> {
+    let metadata = fs::read_to_string(dir.join(METADATA_FILE_NAME)).ok()?;
+    let metadata: CacheMetadata = serde_json::from_str(&metadata).ok()?;
+
+    return Some(CachedTemplates {
+        dir: dir.to_path_buf(),
+        metadata,
+    });
+}
+
+fn cache_key(source: &TemplateSource) -> String {
+    let digest = Sha256::digest(source.to_string().as_bytes());
+
+    return format!("{:x}", digest)[..16].to_string();
+}
+
+fn digest_dir(dir: &Path) -> Result<String, ParraCliError> {
+    let mut hasher = Sha256::new();
+
+    let entries = WalkDir::new(dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter();
+
+    for entry in entries {
+        let entry = entry
+            .map_err(|error| ParraCliError::filesystem(dir, error.into()))?;
+
+        let path = entry.path();
+        let relative_path = path
+            .strip_prefix(dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            hasher.update(format!("d {}\0", relative_path));
+        } else if file_type.is_symlink() {
+            let target = fs::read_link(path)
+                .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+            hasher.update(format!(
+                "l {}\0{}\0",
+                relative_path,
+                target.to_string_lossy()
+            ));
+        } else {
+            let contents = fs::read(path)
+                .map_err(|error| ParraCliError::filesystem(path, error))?;
+
+            hasher.update(format!("f {}\0{}\0", relative_path, contents.len()));
+            hasher.update(&contents);
+        }
+    }
+
+    return Ok(format!("{:x}", hasher.finalize()));
+}
@@ -0,0 +1,946 @@
+use crate::commands::bootstrap::expand_tilde;
+use crate::constants::built::built_info;
+use crate::errors::ParraCliError;
+use crate::http;
+use crate::logger::debug_println;
+use crate::project_generator::generator;
+use crate::template_cache::{self, CachedTemplates};
+use crate::types::templates::TemplateConfig;
+use git2::build::CheckoutBuilder;
+use git2::{
+    AutotagOption, Cred, CredentialType, Direction, ErrorClass, ErrorCode,
+    FetchOptions, Oid, Remote, RemoteCallbacks, Repository,
+};
+use reqwest::Method;
+use semver::Version;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// The repo that the templates for each release of the CLI are published in,
+/// tagged with the CLI's version.
+const DEFAULT_TEMPLATES_REPO: &str =
+    "https://github.com/Parra-Inc/parra-mobile-sdks";
+
+/// Where the fetched commit is stored in the temporary repository.
+const FETCHED_REF: &str = "refs/parra/template";
+
+const MAX_CREDENTIAL_ATTEMPTS: u32 = 3;
+
+/// Where templates are loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateSource {
+    /// A directory containing templates, or a single template.
+    Local(PathBuf),
+    /// A git repository at a branch, tag or full commit hash. The remote's
+    /// default branch is used if there's no reference.
+    Git {
+        url: String,
+        reference: Option<String>,
+    },
+    /// A gzipped tarball, either at a URL or on disk.
+    Tarball(String),
+}
+
+impl TemplateSource {
+    /// The templates published alongside this version of the CLI.
+    pub fn default_remote() -> TemplateSource {
+        return TemplateSource::Git {
+            url: DEFAULT_TEMPLATES_REPO.to_string(),
+            reference: Some(built_info::PKG_VERSION.to_string()),
+        };
+    }
+
+    /// Parses a `--template-source`. Archives are recognized by their
+    /// `.tar.gz` or `.tgz` extension and git repositories by their URL, with
+    /// an optional `#<ref>` suffix. Anything else must be a directory.
+    pub fn parse(source: &str) -> Result<TemplateSource, ParraCliError> {
+        let source = source.trim();
+
+        let path_part = source
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if path_part.ends_with(".tar.gz") || path_part.ends_with(".tgz") {
+            if is_http_url(source) {
+                return Ok(TemplateSource::Tarball(source.to_string()));
+            }
+
+            let path = expand_path(source)?;
+
+            if !path.is_file() {
+                return Err(ParraCliError::InvalidInput(format!(
+                    "The template archive {} doesn't exist.",
+                    path.display()
+                )));
+            }
+
+            return Ok(TemplateSource::Tarball(
+                path.to_string_lossy().to_string(),
+            ));
+        }
+
+        if is_git_url(source) {
+            let (url, reference) = match source.split_once('#') {
+                Some((url, reference)) if !reference.is_empty() => {
+                    (url, Some(reference.to_string()))
+                }
+                Some((url, _)) => (url, None),
+                None => (source, None),
+            };
+
+            return Ok(TemplateSource::Git {
+                url: url.to_string(),
+                reference,
+            });
+        }
+
+        let path = expand_path(source)?;
+
+        if path.is_dir() {
+            return Ok(TemplateSource::Local(path));
+        }
+
+        return Err(ParraCliError::InvalidInput(format!(
+            "The template source \"{}\" isn't a directory, a git URL or a .tar.gz archive.",
+            source
+        )));
+    }
+
+    /// Only the templates directory of the default repo is checked out, since
+    /// the rest of it is the SDK.
+    fn sparse_path(&self) -> Option<&str> {
+        match self {
+            TemplateSource::Git { url, .. }
+                if url == DEFAULT_TEMPLATES_REPO =>
+            {
+                Some("templates")
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for TemplateSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateSource::Local(path) => write!(f, "{}", path.display()),
+            TemplateSource::Git {
+                url,
+                reference: Some(reference),
+            } => write!(f, "{}#{}", url, reference),
+            TemplateSource::Git {
+                url,
+                reference: None,
+            } => write!(f, "{}", url),
+            TemplateSource::Tarball(location) => write!(f, "{}", location),
+        }
+    }
+}
+
+/// The name of the template that's used when none is chosen.
+pub const DEFAULT_TEMPLATE_NAME: &str = "default";
+
+/// A template found in a source.
+#[derive(Debug, Clone)]
+pub struct TemplateSummary {
+    /// The template's directory name, which is what `--template` takes.
+    pub name: String,
+    pub template_dir: PathBuf,
+    /// The directory that symlinks in the template are relative to.
+    pub templates_dir: PathBuf,
+    pub config: TemplateConfig,
+}
+
+impl TemplateSummary {
+    pub fn display_name(&self) -> &str {
+        return self.config.display_name.as_deref().unwrap_or(&self.name);
+    }
+}
+
+/// Every template in a source, once it's been downloaded.
+pub struct FetchedTemplates {
+    pub source: TemplateSource,
+    /// Sorted by name.
+    pub templates: Vec<TemplateSummary>,
+    /// Templates that were skipped because their config couldn't be read.
+    invalid_templates: Vec<InvalidTemplate>,
+    /// Where a local archive was extracted to, which is removed once the
+    /// templates are no longer needed.
+    _download_dir: Option<TempDir>,
+}
+
+/// A template whose config couldn't be read. It's skipped with a warning, and
+/// only fails the command when it's the one that was selected.
+struct InvalidTemplate {
+    name: String,
+    message: String,
+}
+
+impl InvalidTemplate {
+    fn error(&self, source: &TemplateSource) -> ParraCliError {
+        return ParraCliError::InvalidInput(format!(
+            "The {} template in {} can't be used. {}",
+            self.name, source, self.message
+        ));
+    }
+}
+
+impl FetchedTemplates {
+    /// The template named `name`, or the default template when there's no
+    /// name. The default is `default` if the source has it, or the only
+    /// template in sources with just one.
+    pub fn select(
+        &self,
+        name: Option<&str>,
+    ) -> Result<&TemplateSummary, ParraCliError> {
+        if let Some(name) = name {
+            self.check_invalid(name)?;
+
+            return self
+                .templates
+                .iter()
+                .find(|template| template.name == name)
+                .ok_or_else(|| {
+                    ParraCliError::InvalidInput(format!(
+                        "Unknown template \"{}\". {} has: {}.",
+                        name,
+                        self.source,
+                        self.names().join(", ")
+                    ))
+                });
+        }
+
+        if self.default_template().is_none() {
+            self.check_invalid(DEFAULT_TEMPLATE_NAME)?;
+        }
+
+        return self.default_template().ok_or_else(|| {
+            ParraCliError::InvalidInput(format!(
+                "{} has no {} template, so one has to be chosen with --template. It has: {}.",
+                self.source,
+                DEFAULT_TEMPLATE_NAME,
+                self.names().join(", ")
+            ))
+        });
+    }
+
+    pub fn default_template(&self) -> Option<&TemplateSummary> {
+        if let [template] = self.templates.as_slice() {
+            return Some(template);
+        }
+
+        return self
+            .templates
+            .iter()
+            .find(|template| template.name == DEFAULT_TEMPLATE_NAME);
+    }
+
+    fn check_invalid(&self, name: &str) -> Result<(), ParraCliError> {
+        if let Some(template) = self
+            .invalid_templates
+            .iter()
+            .find(|template| template.name == name)
+        {
+            return Err(template.error(&self.source));
+        }
+
+        return Ok(());
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        return self
+            .templates
+            .iter()
+            .map(|template| template.name.as_str())
+            .collect();
+    }
+}
+
+/// A template that's ready to be rendered.
+pub struct ResolvedTemplate {
+    /// The template's XcodeGen spec, with the package definitions appended.
+    pub project_spec: String,
+    pub template_dir: PathBuf,
+    /// The directory that symlinks in the template are relative to.
+    pub templates_dir: PathBuf,
+    pub config: TemplateConfig,
+}
+
+impl ResolvedTemplate {
+    /// Reads the template's project spec, after checking that this version of
+    /// the CLI can generate it.
+    pub fn load(
+        template: &TemplateSummary,
+        use_local_packages: bool,
+    ) -> Result<ResolvedTemplate, ParraCliError> {
+        if let Some(min_sdk_version) = &template.config.min_sdk_version {
+            let current = Version::parse(built_info::PKG_VERSION).ok();
+            let minimum = Version::parse(min_sdk_version).ok();
+
+            if let (Some(current), Some(minimum)) = (current, minimum) {
+                if current < minimum {
+                    return Err(ParraCliError::InvalidInput(format!(
+                        "The {} template requires version {} of the Parra SDK or later, but this version of the CLI generates projects for {}. Update the CLI to use this template.",
+                        template.name, minimum, current
+                    )));
+                }
+            }
+        }
+
+        if use_local_packages {
+            println!("Using local packages for project generation.");
+        } else {
+            println!("Using remote packages for project generation.");
+        }
+
+        let project_spec = generator::read_project_spec(
+            &template.template_dir,
+            &template.templates_dir,
+            use_local_packages,
+        )?;
+
+        return Ok(ResolvedTemplate {
+            project_spec,
+            template_dir: template.template_dir.clone(),
+            templates_dir: template.templates_dir.clone(),
+            config: template.config.clone(),
+        });
+    }
+}
+
+/// A uniquely named directory that's removed along with its contents when
+/// dropped, so that downloads and previews are cleaned up whether or not they
+/// succeed.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn create_in(
+        parent: &Path,
+        prefix: &str,
+    ) -> Result<TempDir, ParraCliError> {
+        let path =
+            parent.join(format!("{}-{:016x}", prefix, rand::random::<u64>()));
+
+        fs::create_dir_all(&path)
+            .map_err(|error| ParraCliError::filesystem(&path, error))?;
+
+        return Ok(TempDir { path });
+    }
+
+    pub fn path(&self) -> &Path {
+        return &self.path;
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Loads every template in the source. Remote sources are cached, see
+/// `template_cache`. Local archives are extracted to a temporary directory,
+/// which is removed when the returned templates are dropped, or right away if
+/// loading fails.
+pub async fn fetch_templates(
+    source: &TemplateSource,
+) -> Result<FetchedTemplates, ParraCliError> {
+    let mut download_dir = None;
+
+    let root = match source {
+        TemplateSource::Local(root) => root.clone(),
+        TemplateSource::Git { url, reference } => {
+            fetch_cached_git_source(source, url, reference.as_deref())?
+                .content_dir()
+        }
+        TemplateSource::Tarball(location) if is_http_url(location) => {
+            fetch_cached_tarball(source, location).await?.content_dir()
+        }
+        TemplateSource::Tarball(location) => {
+            let root = download_dir.insert(TempDir::create_in(
+                &std::env::temp_dir(),
+                ".parra-templates",
+            )?);
+
+            extract_tarball(location, &root.path).await?
+        }
+    };
+
+    let (templates, invalid_templates) = discover_templates(&root)?;
+
+    if templates.is_empty() {
+        if let Some(template) = invalid_templates.first() {
+            return Err(template.error(source));
+        }
+
+        return Err(ParraCliError::InvalidInput(format!(
+            "Couldn't find any templates in {}. Expected a template, a directory of templates, or a directory containing a templates directory.",
+            source
+        )));
+    }
+
+    return Ok(FetchedTemplates {
+        source: source.clone(),
+        templates,
+        invalid_templates,
+        _download_dir: download_dir,
+    });
+}
+
+/// Sources are either a single template, or a directory containing templates
+/// either directly or in a `templates` directory, like this repo. Templates
+/// are directories with an `App` directory. In a directory of templates, ones
+/// whose config can't be read are returned separately with a warning.
+fn discover_templates(
+    root: &PathBuf,
+) -> Result<(Vec<TemplateSummary>, Vec<InvalidTemplate>), ParraCliError> {
+    if is_template(root) {
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| DEFAULT_TEMPLATE_NAME.to_string());
+        let templates_dir = root
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| root.clone());
+
+        return Ok((
+            vec![read_template_summary(name, root, &templates_dir)?],
+            vec![],
+        ));
+    }
+
+    let templates_dir = if root.join("templates").is_dir() {
+        root.join("templates")
+    } else {
+        root.clone()
+    };
+
+    let entries = fs::read_dir(&templates_dir)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(|error| ParraCliError::filesystem(&templates_dir, error))?;
+
+    let mut templates = vec![];
+    let mut invalid_templates = vec![];
+
+    for entry in entries {
+        let template_dir = entry.path();
+
+        if !is_template(&template_dir) {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        match read_template_summary(name.clone(), &template_dir, &templates_dir)
+        {
+            Ok(template) => templates.push(template),
+            Err(error) => {
+                eprintln!("Skipping the {} template: {}", name, error);
+
+                invalid_templates.push(InvalidTemplate {
+                    name,
+                    message: error.to_string(),
+                });
+            }
+        }
+    }
+
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    invalid_templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+    return Ok((templates, invalid_templates));
+}
+
+fn read_template_summary(
+    name: String,
+    template_dir: &PathBuf,
+    templates_dir: &PathBuf,
+) -> Result<TemplateSummary, ParraCliError> {
+    let config = generator::read_template_config(template_dir, templates_dir)?;
+
+    return Ok(TemplateSummary {
+        name,
+        template_dir: template_dir.clone(),
+        templates_dir: templates_dir.clone(),
+        config,
+    });
+}
+
+fn is_template(dir: &Path) -> bool {
+    return dir.join("App").is_dir();
+}
+
+/// Uses the cached templates if the remote reference still points at the
+/// cached commit, and otherwise fetches them. Falls back to the cache when
+/// the remote can't be reached, so that bootstrapping works offline.
+fn fetch_cached_git_source(
+    source: &TemplateSource,
+    url: &str,
+    reference: Option<&str>,
+) -> Result<CachedTemplates, ParraCliError> {
+    let cached = template_cache::find(source);
+
+    let (source_ref, commit) = match resolve_git_reference(url, reference) {
+        Ok(resolved) => resolved,
+        // The remote was reachable, but doesn't have the reference.
+        Err(error @ ParraCliError::InvalidInput(_)) => return Err(error),
+        Err(error) => {
+            let Some(cached) = cached else {
+                return Err(error);
+            };
+
+            let fetched_at = cached
+                .fetched_at()
+                .map(|date| date.format(" from %Y-%m-%d").to_string())
+                .unwrap_or_default();
+
+            eprintln!(
+                "{}\nUsing the cached templates{} instead.",
+                error, fetched_at
+            );
+
+            return Ok(cached);
+        }
+    };
+
+    if let Some(cached) = cached {
+        if cached.metadata.commit.as_deref() == Some(&commit.to_string()) {
+            debug_println!("Using cached templates for {}", source);
+
+            return Ok(cached);
+        }
+    }
+
+    let staging_dir = staging_dir()?;
+
+    clone_git_source(
+        url,
+        &source_ref,
+        source.sparse_path(),
+        &staging_dir.path,
+    )?;
+
+    // Only the checked out files are cached.
+    let git_dir = staging_dir.path.join(".git");
+
+    fs::remove_dir_all(&git_dir)
+        .map_err(|error| ParraCliError::filesystem(&git_dir, error))?;
+
+    return template_cache::store(
+        source,
+        Some(commit.to_string()),
+        &staging_dir.path,
+    );
+}
+
+/// Archives at a URL are assumed not to change, so they're only downloaded if
+/// they aren't cached.
+async fn fetch_cached_tarball(
+    source: &TemplateSource,
+    url: &str,
+) -> Result<CachedTemplates, ParraCliError> {
+    if let Some(cached) = template_cache::find(source) {
+        debug_println!("Using cached templates for {}", source);
+
+        return Ok(cached);
+    }
+
+    let staging_dir = staging_dir()?;
+    let extracted = extract_tarball(url, &staging_dir.path).await?;
+
+    return template_cache::store(source, None, &extracted);
+}
+
+/// Downloads are staged in the cache directory, so that they can be moved
+/// into the cache without copying them.
+fn staging_dir() -> Result<TempDir, ParraCliError> {
+    let cache_dir = template_cache::cache_dir();
+
+    fs::create_dir_all(&cache_dir)
+        .map_err(|error| ParraCliError::filesystem(&cache_dir, error))?;
+
+    return TempDir::create_in(&cache_dir, template_cache::STAGING_DIR_PREFIX);
+}
+
+/// Finds the reference to fetch for a branch, tag or commit hash, or the
+/// remote's default branch when there's no reference, and the commit it
+/// points to. Listing the remote's references first means that a missing
+/// branch or tag can be reported as such, and is much cheaper than fetching.
+fn resolve_git_reference(
+    url: &str,
+    reference: Option<&str>,
+) -> Result<(String, Oid), ParraCliError> {
+    let git_error = |error: git2::Error| git_fetch_error(url, error);
+
+    let mut remote = Remote::create_detached(url).map_err(git_error)?;
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(remote_callbacks()), None)
+        .map_err(git_error)?;
+    let heads = connection.list().map_err(git_error)?;
+
+    let Some(reference) = reference else {
+        return heads
+            .iter()
+            .find(|head| head.name() == "HEAD")
+            .map(|head| ("HEAD".to_string(), head.oid()))
+            .ok_or_else(|| {
+                ParraCliError::external_tool(
+                    "git",
+                    format!("{} doesn't have a default branch.", url),
+                )
+            });
+    };
+
+    let candidates = [
+        reference.to_string(),
+        format!("refs/tags/{}", reference),
+        format!("refs/heads/{}", reference),
+    ];
+
+    for candidate in candidates {
+        if let Some(head) = heads.iter().find(|head| head.name() == candidate) {
+            return Ok((candidate, head.oid()));
+        }
+    }
+
+    // Commits aren't advertised, so a full hash is fetched directly. Whether
+    // that's allowed depends on the host.
+    if reference.len() == 40 {
+        if let Ok(oid) = Oid::from_str(reference) {
+            return Ok((reference.to_string(), oid));
+        }
+    }
+
+    return Err(ParraCliError::InvalidInput(format!(
+        "{} has no branch or tag named \"{}\". Use a branch, a tag or a full commit hash.",
+        url, reference
+    )));
+}
+
+/// Checks out `source_ref` into `target_dir`, limited to `sparse_path` when
+/// given. Only that commit is fetched.
+fn clone_git_source(
+    url: &str,
+    source_ref: &str,
+    sparse_path: Option<&str>,
+    target_dir: &PathBuf,
+) -> Result<(), ParraCliError> {
+    let git_error = |error: git2::Error| git_fetch_error(url, error);
+
+    let repo = Repository::init(target_dir)?;
+    let mut remote = repo.remote_anonymous(url).map_err(git_error)?;
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options
+        .remote_callbacks(remote_callbacks())
+        .download_tags(AutotagOption::None);
+
+    // libgit2's local transport doesn't support shallow fetches.
+    if !url.starts_with("file://") {
+        fetch_options.depth(1);
+    }
+
+    remote
+        .fetch(
+            &[format!("+{}:{}", source_ref, FETCHED_REF)],
+            Some(&mut fetch_options),
+            None,
+        )
+        .map_err(git_error)?;
+
+    let commit = repo.find_reference(FETCHED_REF)?.peel_to_commit()?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+
+    if let Some(sparse_path) = sparse_path {
+        checkout.path(sparse_path);
+    }
+
+    repo.checkout_tree(commit.as_object(), Some(&mut checkout))?;
+
+    return Ok(());
+}
+
+/// Credentials come from the SSH agent or git's credential helpers, the same
+/// places the git command line would find them.
+fn remote_callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+
+    callbacks.credentials(move |url, username, allowed| {
+        // libgit2 keeps asking for credentials for as long as they're
+        // rejected.
+        attempts += 1;
+
+        if attempts > MAX_CREDENTIAL_ATTEMPTS {
+            return Err(git2::Error::new(
+                ErrorCode::Auth,
+                ErrorClass::Net,
+                "no valid credentials were found",
+            ));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            let config = git2::Config::open_default()?;
+
+            return Cred::credential_helper(&config, url, username);
+        }
+
+        return Cred::default();
+    });
+
+    return callbacks;
+}
+
+fn git_fetch_error(url: &str, error: git2::Error) -> ParraCliError {
+    if error.code() == ErrorCode::Auth {
+        return ParraCliError::Auth(format!(
+            "Couldn't authenticate with {}: {}",
+            url,
+            error.message()
+        ));
+    }
+
+    let message = match error.class() {
+        // Connection failures are reported as OS errors.
+        ErrorClass::Net
+        | ErrorClass::Http
+        | ErrorClass::Ssl
+        | ErrorClass::Ssh
+        | ErrorClass::Os => {
+            format!("Couldn't reach {}: {}", url, error.message())
+        }
+        _ => format!(
+            "Couldn't fetch templates from {}: {}",
+            url,
+            error.message()
+        ),
+    };
+
+    return ParraCliError::external_tool("git", message);
+}
+
+/// Extracts the archive into `target_dir`, returning the directory that its
+/// contents are in. Archives like the ones GitHub generates contain a single
+/// top level directory, which is descended into.
+async fn extract_tarball(
+    location: &str,
+    target_dir: &Path,
+) -> Result<PathBuf, ParraCliError> {
+    let archive_path = if is_http_url(location) {
+        let response = http::send_with_retry(&Method::GET, || {
+            http::client().get(location)
+        })
+        .await?
+        .error_for_status()?;
+
+        let archive_path = target_dir.join("template.tar.gz");
+        let bytes = response.bytes().await?;
+
+        fs::write(&archive_path, bytes)
+            .map_err(|error| ParraCliError::filesystem(&archive_path, error))?;
+
+        archive_path
+    } else {
+        PathBuf::from(location)
+    };
+
+    let extracted_dir = target_dir.join("extracted");
+
+    fs::create_dir_all(&extracted_dir)
+        .map_err(|error| ParraCliError::filesystem(&extracted_dir, error))?;
+
+    run_tool(
+        Command::new("tar")
+            .arg("-xzf")
+            .arg(&archive_path)
+            .arg("-C")
+            .arg(&extracted_dir),
+        "tar",
+    )?;
+
+    let entries = fs::read_dir(&extracted_dir)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(|error| ParraCliError::filesystem(&extracted_dir, error))?;
+
+    if let [entry] = entries.as_slice() {
+        if entry.path().is_dir() {
+            return Ok(entry.path());
+        }
+    }
+
+    return Ok(extracted_dir);
+}
+
+/// Runs a command to completion, treating a non-zero exit status as an error.
+fn run_tool(
+    command: &mut Command,
+    tool: &str,
+) -> Result<Output, ParraCliError> {
+    let output = command
+        .output()
+        .map_err(|error| ParraCliError::external_tool(tool, error))?;
+
+    if !output.status.success() {
+        return Err(ParraCliError::external_tool(
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim(),
+        ));
+    }
+
+    return Ok(output);
+}
+
+fn expand_path(path: &str) -> Result<PathBuf, ParraCliError> {
+    return expand_tilde(path).ok_or_else(|| {
+        ParraCliError::InvalidInput(format!(
+            "Couldn't expand {} because the home directory is unknown",
+            path
+        ))
+    });
+}
+
+fn is_http_url(source: &str) -> bool {
+    return source.starts_with("https://") || source.starts_with("http://");
+}
+
+/// Besides URLs, git accepts scp-like addresses such as
+/// `git@github.com:org/repo.git`.
+fn is_git_url(source: &str) -> bool {
+    let has_scheme = ["https://", "http://", "ssh://", "git://", "file://"]
+        .iter()
+        .any(|scheme| source.starts_with(scheme));
+
+    let is_scp_like = !source.contains("://")
+        && source
+            .split_once(':')
+            .is_some_and(|(host, _)| host.contains('@'));
+
+    return has_scheme || is_scp_like;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_dir() -> TempDir {
+        return TempDir::create_in(&env::temp_dir(), "parra-source-test")
+            .unwrap();
+    }
+
+    fn git(url: &str, reference: Option<&str>) -> TemplateSource {
+        return TemplateSource::Git {
+            url: url.to_string(),
+            reference: reference.map(str::to_string),
+        };
+    }
+
+    #[test]
+    fn parses_git_urls_with_references() {
+        let url = "https://github.com/org/templates.git";
+
+        assert_eq!(TemplateSource::parse(url).unwrap(), git(url, None));
+        assert_eq!(
+            TemplateSource::parse(&format!(" {}#v1.2.0 ", url)).unwrap(),
+            git(url, Some("v1.2.0"))
+        );
+        assert_eq!(
+            TemplateSource::parse(&format!("{}#", url)).unwrap(),
+            git(url, None)
+        );
+    }
+
+    #[test]
+    fn parses_scp_like_and_other_git_urls() {
+        for url in [
+            "git@github.com:org/templates.git",
+            "ssh://git@github.com/org/templates.git",
+            "git://example.com/templates.git",
+            "file:///tmp/templates.git",
+        ] {
+            assert_eq!(TemplateSource::parse(url).unwrap(), git(url, None));
+        }
+    }
+
+    #[test]
+    fn parses_remote_tarballs() {
+        for url in [
+            "https://example.com/templates.tar.gz",
+            "http://example.com/templates.TGZ?token=abc",
+        ] {
+            assert_eq!(
+                TemplateSource::parse(url).unwrap(),
+                TemplateSource::Tarball(url.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn parses_local_tarballs() {
+        let dir = temp_dir();
+        let archive = dir.path().join("templates.tgz");
+        fs::write(&archive, "").unwrap();
+
+        assert_eq!(
+            TemplateSource::parse(&archive.to_string_lossy()).unwrap(),
+            TemplateSource::Tarball(archive.to_string_lossy().to_string())
+        );
+
+        let missing = dir.path().join("missing.tar.gz");
+
+        assert!(matches!(
+            TemplateSource::parse(&missing.to_string_lossy()),
+            Err(ParraCliError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn parses_local_directories() {
+        let dir = temp_dir();
+
+        assert_eq!(
+            TemplateSource::parse(&dir.path().to_string_lossy()).unwrap(),
+            TemplateSource::Local(dir.path().to_path_buf())
+        );
+
+        let missing = dir.path().join("missing");
+
+        assert!(matches!(
+            TemplateSource::parse(&missing.to_string_lossy()),
+            Err(ParraCliError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn displays_sources_as_they_are_parsed() {
+        let source = "https://github.com/org/templates.git#main";
+
+        assert_eq!(TemplateSource::parse(source).unwrap().to_string(), source);
+    }
+
+    #[test]
+    fn only_checks_out_templates_from_the_default_repo() {
+        assert_eq!(
+            TemplateSource::default_remote().sparse_path(),
+            Some("templates")
+        );
+        assert_eq!(
+            git("https://github.com/org/templates.git", None).sparse_path(),
+            None
+        );
+    }
+}
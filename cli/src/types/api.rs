@@ -7,7 +7,7 @@ use crate::types::theme::ResolvedTheme;
 use super::auth::Credential;
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct UserResponse {
     pub id: String,
     /// Will be nil in cases where the user registered for a new account during auth
@@ -245,16 +245,6 @@ pub enum AppTab {
     AppSettingsTab(AppSettingsTabData),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "kebab-case")]
-pub enum AppTemplate {
-    Default,
-    Podcast,
-    RevenueCat,
-    ShopifyStorefront,
-    YC,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppBootstrapTemplate {
     pub tabs: Vec<AppTab>,
@@ -266,8 +256,9 @@ pub struct AppBootstrapResponseBody {
     pub tenant_id: String,
     #[serde(rename = "application_id")]
     pub application_id: String,
+    /// The name of the template, matching its directory name.
     #[serde(rename = "template")]
-    pub template: AppTemplate,
+    pub template: String,
     pub tabs: Vec<AppTab>,
     pub themes: Vec<ResolvedTheme>,
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::api::UserResponse;
+
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct Credential {
     pub token: String,
@@ -7,6 +9,13 @@ pub struct Credential {
     pub expiry: u64,
 }
 
+/// The subset of an access token's JWT payload that the CLI cares about.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AccessTokenClaims {
+    /// Expiration time, in seconds since the Unix epoch.
+    pub exp: u64,
+}
+
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct AuthResponse {
     pub access_token: String,
@@ -19,6 +28,10 @@ pub struct AuthResponse {
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct RefreshResponse {
     pub access_token: String,
+    /// Only sent when the auth server rotates refresh tokens, in which case
+    /// the previous one can't be used again.
+    #[serde(default)]
+    pub refresh_token: Option<String>,
     pub scope: String,
     pub expires_in: u64,
     pub token_type: String,
@@ -43,3 +56,20 @@ pub struct TokenRequest {
     pub device_code: String,
     pub client_id: String,
 }
+
+/// The output of `parra whoami`, also emitted as JSON with --json.
+#[derive(Debug, Serialize)]
+pub struct AuthStatus {
+    pub logged_in: bool,
+    pub profile: String,
+    /// Either `PARRA_TOKEN` or the name of the credential store backend.
+    pub credential_source: String,
+    pub user: Option<UserResponse>,
+    /// RFC 3339 timestamp of when the stored access token expires.
+    pub token_expires_at: Option<String>,
+    /// Whether the stored access token had to be refreshed before it could be
+    /// used.
+    pub refresh_required: bool,
+    /// Whether a refresh token is available to renew the access token.
+    pub can_refresh: bool,
+}
@@ -1,4 +1,10 @@
+use regex::Regex;
+use reqwest::Url;
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
 
 use crate::types::{
     api::{
@@ -11,15 +17,68 @@ use crate::types::{
 
 use super::api::Icon;
 
+/// The newest version of the template `config.json` format that this version
+/// of the CLI understands. Bump it whenever the format changes in a way that
+/// older versions can't read.
+pub const TEMPLATE_CONFIG_SCHEMA_VERSION: u32 = 3;
+
+/// The first schema version with input types and conditional inputs.
+const TYPED_INPUTS_SCHEMA_VERSION: u32 = 2;
+
+/// The first schema version with a display name, description, required tabs
+/// and minimum SDK version.
+const METADATA_SCHEMA_VERSION: u32 = 3;
+
+/// The top level names in the liquid globals that come from the project
+/// context, which input groups can't use.
+const RESERVED_INPUT_GROUP_NAMES: &[&str] =
+    &["app", "tenant", "sdk", "config", "template"];
+
+#[derive(Debug, Deserialize, Clone, Copy, Serialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CliInputType {
+    #[default]
+    Text,
+    /// One of a fixed list of `options`.
+    Select,
+    Bool,
+    Number,
+    /// An http or https URL.
+    Url,
+    /// A hex color like #FF8800, with an optional alpha component.
+    Color,
+}
+
+impl Display for CliInputType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let output = match self {
+            CliInputType::Text => "text",
+            CliInputType::Select => "select",
+            CliInputType::Bool => "bool",
+            CliInputType::Number => "number",
+            CliInputType::Url => "url",
+            CliInputType::Color => "color",
+        };
+
+        write!(f, "{}", output)
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct CliInput {
+    #[serde(rename = "type", default)]
+    pub input_type: CliInputType,
+
     #[serde(default = "default_required")]
     pub required: bool,
 
     pub prompt: String,
     pub help_message: Option<String>,
     pub default_message: Option<String>,
-    pub default: Option<String>,
+    /// Either a string in the same format as a typed answer, or a value of the
+    /// input's type, e.g. `true` for a bool.
+    pub default: Option<Value>,
     pub key: String,
 
     #[serde(default = "default_min_length")]
@@ -27,6 +86,211 @@ pub struct CliInput {
 
     #[serde(default = "default_max_length")]
     pub max_length: usize,
+
+    /// The choices for a select input.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+
+    /// A regular expression that the whole of a text answer must match.
+    pub pattern: Option<String>,
+
+    /// The bounds of a number input, inclusive.
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+
+    /// The input is only asked for when every earlier input named here was
+    /// answered with the given value. Inputs in the same group are named by
+    /// their key, and others by `<group>.<key>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub when: BTreeMap<String, Value>,
+}
+
+impl CliInput {
+    /// Parses and validates an answer typed at a prompt or passed on the
+    /// command line, returning it as its proper type.
+    pub fn parse_answer(&self, raw: &str) -> Result<Value, String> {
+        let answer = raw.trim();
+
+        if answer.is_empty() {
+            if self.required {
+                return Err("A value is required.".to_string());
+            }
+
+            return Ok(self.empty_answer());
+        }
+
+        match self.input_type {
+            CliInputType::Text => {
+                let length = answer.chars().count();
+
+                if length < self.min_length {
+                    return Err(format!(
+                        "Must be at least {} characters.",
+                        self.min_length
+                    ));
+                }
+
+                if length > self.max_length {
+                    return Err(format!(
+                        "Must be at most {} characters.",
+                        self.max_length
+                    ));
+                }
+
+                if let Some(pattern) = &self.pattern {
+                    let regex = anchored_regex(pattern)?;
+
+                    if !regex.is_match(answer) {
+                        return Err(format!("Must match {}.", pattern));
+                    }
+                }
+
+                Ok(Value::String(answer.to_string()))
+            }
+            CliInputType::Select => {
+                if !self.options.iter().any(|option| option == answer) {
+                    return Err(format!(
+                        "Must be one of: {}.",
+                        self.options.join(", ")
+                    ));
+                }
+
+                Ok(Value::String(answer.to_string()))
+            }
+            CliInputType::Bool => match answer.to_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Ok(Value::Bool(true)),
+                "false" | "no" | "n" | "0" => Ok(Value::Bool(false)),
+                _ => Err("Must be true or false.".to_string()),
+            },
+            CliInputType::Number => {
+                let number = answer
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .ok_or_else(|| "Must be a number.".to_string())?;
+
+                self.number_value(number)
+            }
+            CliInputType::Url => {
+                let url = Url::parse(answer)
+                    .map_err(|error| format!("Must be a URL: {}.", error))?;
+
+                if url.scheme() != "http" && url.scheme() != "https" {
+                    return Err("Must be an http or https URL.".to_string());
+                }
+
+                Ok(Value::String(answer.to_string()))
+            }
+            CliInputType::Color => {
+                let hex_color =
+                    Regex::new(r"^#?([0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap();
+
+                if !hex_color.is_match(answer) {
+                    return Err(
+                        "Must be a hex color like #FF8800 or #FF880080."
+                            .to_string(),
+                    );
+                }
+
+                Ok(Value::String(format!(
+                    "#{}",
+                    answer.trim_start_matches('#').to_uppercase()
+                )))
+            }
+        }
+    }
+
+    /// Validates a value from JSON, like a default or an answer in a
+    /// manifest, which may be either a string or a value of the input's type.
+    pub fn parse_value(&self, value: &Value) -> Result<Value, String> {
+        match (value, self.input_type) {
+            (Value::String(raw), _) => self.parse_answer(raw),
+            (Value::Bool(_), CliInputType::Bool) => Ok(value.clone()),
+            (Value::Number(number), CliInputType::Number) => {
+                self.number_value(number.as_f64().unwrap_or_default())
+            }
+            (Value::Null, _) if !self.required => Ok(self.empty_answer()),
+            _ => Err(format!("Expected a {} value.", self.input_type)),
+        }
+    }
+
+    /// The value used when an optional input is left empty.
+    pub fn empty_answer(&self) -> Value {
+        match self.input_type {
+            CliInputType::Text | CliInputType::Url | CliInputType::Color => {
+                Value::String(String::new())
+            }
+            CliInputType::Bool => Value::Bool(false),
+            CliInputType::Select | CliInputType::Number => Value::Null,
+        }
+    }
+
+    /// Whole numbers are kept as integers, so that they render without a
+    /// fractional part.
+    fn number_value(&self, number: f64) -> Result<Value, String> {
+        if let Some(min) = self.min {
+            if number < min {
+                return Err(format!("Must be at least {}.", min));
+            }
+        }
+
+        if let Some(max) = self.max {
+            if number > max {
+                return Err(format!("Must be at most {}.", max));
+            }
+        }
+
+        if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+            return Ok(Value::from(number as i64));
+        }
+
+        Ok(Value::from(number))
+    }
+
+    fn validate(&self, group: &str) -> Result<(), String> {
+        let is_select = self.input_type == CliInputType::Select;
+        let is_text = self.input_type == CliInputType::Text;
+        let is_number = self.input_type == CliInputType::Number;
+
+        if is_select == self.options.is_empty() {
+            return Err(if is_select {
+                "Select inputs must have options.".to_string()
+            } else {
+                "Only select inputs can have options.".to_string()
+            });
+        }
+
+        if let Some(pattern) = &self.pattern {
+            if !is_text {
+                return Err("Only text inputs can have a pattern.".to_string());
+            }
+
+            anchored_regex(pattern)?;
+        }
+
+        if !is_number && (self.min.is_some() || self.max.is_some()) {
+            return Err("Only number inputs can have a min or max.".to_string());
+        }
+
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err("min can't be greater than max.".to_string());
+            }
+        }
+
+        if let Some(default) = &self.default {
+            self.parse_value(default).map_err(|error| {
+                format!("Invalid default for {}.{}: {}", group, self.key, error)
+            })?;
+        }
+
+        return Ok(());
+    }
+}
+
+fn anchored_regex(pattern: &str) -> Result<Regex, String> {
+    Regex::new(&format!("^(?:{})$", pattern))
+        .map_err(|error| format!("Invalid pattern {}: {}", pattern, error))
 }
 
 fn default_min_length() -> usize {
@@ -41,15 +305,200 @@ fn default_required() -> bool {
     return true;
 }
 
+/// A set of related inputs. Answers are available to templates under the
+/// group's name, e.g. `{{ revenue_cat.api_key }}`.
 #[derive(Debug, Deserialize, Clone, Serialize)]
-pub struct InputConfig {
+#[serde(deny_unknown_fields)]
+pub struct InputGroup {
     pub name: String,
     pub inputs: Vec<CliInput>,
 }
 
+/// The contents of a template's `config.json`.
 #[derive(Debug, Deserialize, Clone, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct TemplateConfig {
-    pub cli_input: Option<InputConfig>,
+    /// The version of the format that the file is written in.
+    pub schema_version: u32,
+    /// A human readable name, e.g. "Shopify Storefront". Defaults to the
+    /// template's directory name.
+    pub display_name: Option<String>,
+    /// A sentence or two about what the generated app does.
+    pub description: Option<String>,
+    /// The tabs the generated app has, e.g. "sample" or "settings". Any name
+    /// is accepted, so that templates can add tabs this version of the CLI
+    /// doesn't know about yet.
+    #[serde(default)]
+    pub required_tabs: Vec<String>,
+    /// The oldest version of the Parra SDK that the template's code builds
+    /// against, e.g. "0.34.0".
+    pub min_sdk_version: Option<String>,
+    /// The lowest iOS version the template's code can be built for, e.g.
+    /// "17.0".
+    pub min_deployment_target: Option<String>,
+    #[serde(default)]
+    pub input_groups: Vec<InputGroup>,
+}
+
+impl TemplateConfig {
+    /// Parses and validates a `config.json`. The schema version is checked
+    /// before anything else, so that a template written for a newer CLI is
+    /// reported as such instead of as a list of unknown fields.
+    pub fn from_json(contents: &str) -> Result<TemplateConfig, String> {
+        let value: serde_json::Value = serde_json::from_str(contents)
+            .map_err(|error| error.to_string())?;
+
+        let Some(schema_version) = value.get("schema_version") else {
+            return Err(format!(
+                "Missing schema_version. Configs written before versioning was introduced used cli_input or cli_inputs, which have been replaced by input_groups. Add \"schema_version\": {} and move any inputs into input_groups.",
+                TEMPLATE_CONFIG_SCHEMA_VERSION
+            ));
+        };
+
+        match schema_version.as_u64() {
+            Some(version)
+                if version > TEMPLATE_CONFIG_SCHEMA_VERSION as u64 =>
+            {
+                return Err(format!(
+                    "Uses schema version {}, but this version of the CLI only supports up to version {}. Update the CLI to use this template.",
+                    version, TEMPLATE_CONFIG_SCHEMA_VERSION
+                ));
+            }
+            Some(version) if version > 0 => {}
+            _ => {
+                return Err(format!(
+                    "Invalid schema_version {}. Expected a positive integer.",
+                    schema_version
+                ));
+            }
+        }
+
+        let config = serde_json::from_str::<TemplateConfig>(contents)
+            .map_err(|error| error.to_string())?;
+
+        config.validate()?;
+
+        return Ok(config);
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let uses_metadata = self.display_name.is_some()
+            || self.description.is_some()
+            || !self.required_tabs.is_empty()
+            || self.min_sdk_version.is_some();
+
+        if uses_metadata && self.schema_version < METADATA_SCHEMA_VERSION {
+            return Err(format!(
+                "display_name, description, required_tabs and min_sdk_version require schema_version {}.",
+                METADATA_SCHEMA_VERSION
+            ));
+        }
+
+        if let Some(min_sdk_version) = &self.min_sdk_version {
+            if Version::parse(min_sdk_version).is_err() {
+                return Err(format!(
+                    "Invalid min_sdk_version \"{}\". Expected a version like 1.2.3.",
+                    min_sdk_version
+                ));
+            }
+        }
+
+        let mut group_names = HashSet::new();
+        // Conditions may only refer to inputs that are asked for earlier.
+        let mut earlier_inputs = HashSet::new();
+
+        for group in &self.input_groups {
+            if !is_identifier(&group.name) {
+                return Err(format!(
+                    "Invalid input group name \"{}\". Names may only contain letters, numbers and underscores, and can't start with a number.",
+                    group.name
+                ));
+            }
+
+            if RESERVED_INPUT_GROUP_NAMES.contains(&group.name.as_str()) {
+                return Err(format!(
+                    "The input group name \"{}\" is reserved for the project context.",
+                    group.name
+                ));
+            }
+
+            if !group_names.insert(&group.name) {
+                return Err(format!(
+                    "The input group \"{}\" is defined more than once.",
+                    group.name
+                ));
+            }
+
+            let mut keys = HashSet::new();
+
+            for input in &group.inputs {
+                if !is_identifier(&input.key) {
+                    return Err(format!(
+                        "Invalid input key \"{}.{}\". Keys may only contain letters, numbers and underscores, and can't start with a number.",
+                        group.name, input.key
+                    ));
+                }
+
+                if !keys.insert(&input.key) {
+                    return Err(format!(
+                        "The input \"{}.{}\" is defined more than once.",
+                        group.name, input.key
+                    ));
+                }
+
+                let uses_typed_inputs = input.input_type != CliInputType::Text
+                    || input.pattern.is_some()
+                    || !input.when.is_empty();
+
+                if uses_typed_inputs
+                    && self.schema_version < TYPED_INPUTS_SCHEMA_VERSION
+                {
+                    return Err(format!(
+                        "The input \"{}.{}\" uses input types, patterns or conditions, which require schema_version {}.",
+                        group.name, input.key, TYPED_INPUTS_SCHEMA_VERSION
+                    ));
+                }
+
+                input.validate(&group.name)?;
+
+                for reference in input.when.keys() {
+                    let qualified = qualified_input_key(&group.name, reference);
+
+                    if !earlier_inputs.contains(&qualified) {
+                        return Err(format!(
+                            "The input \"{}.{}\" depends on \"{}\", which isn't an earlier input.",
+                            group.name, input.key, reference
+                        ));
+                    }
+                }
+
+                earlier_inputs.insert(format!("{}.{}", group.name, input.key));
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+/// Condition references are relative to the input's group unless they name
+/// another group.
+pub fn qualified_input_key(group: &str, reference: &str) -> String {
+    if reference.contains('.') {
+        return reference.to_string();
+    }
+
+    return format!("{}.{}", group, reference);
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
@@ -360,3 +809,313 @@ pub struct ProjectContext {
     pub config: TemplateConfig,
     pub template: TemplateInfo,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn input(definition: Value) -> CliInput {
+        let mut definition = definition;
+        definition["key"] = json!("key");
+        definition["prompt"] = json!("Prompt");
+
+        return serde_json::from_value(definition).unwrap();
+    }
+
+    fn config_error(config: Value) -> String {
+        return TemplateConfig::from_json(&config.to_string())
+            .expect_err("the config should be rejected");
+    }
+
+    fn config_with_groups(groups: Value) -> Value {
+        return json!({
+            "schema_version": TEMPLATE_CONFIG_SCHEMA_VERSION,
+            "input_groups": groups,
+        });
+    }
+
+    #[test]
+    fn parses_text_answers() {
+        let text = input(json!({ "min_length": 2, "max_length": 4 }));
+
+        assert_eq!(text.parse_answer(" ab "), Ok(json!("ab")));
+        assert!(text.parse_answer("a").is_err());
+        assert!(text.parse_answer("abcde").is_err());
+        assert_eq!(
+            text.parse_answer(""),
+            Err("A value is required.".to_string())
+        );
+
+        let optional = input(json!({ "required": false }));
+
+        assert_eq!(optional.parse_answer("  "), Ok(json!("")));
+    }
+
+    #[test]
+    fn anchors_text_patterns() {
+        let text = input(json!({ "pattern": "[a-z]+" }));
+
+        assert_eq!(text.parse_answer("abc"), Ok(json!("abc")));
+        assert_eq!(
+            text.parse_answer("abc1"),
+            Err("Must match [a-z]+.".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_select_answers() {
+        let select = input(json!({ "type": "select", "options": ["a", "b"] }));
+
+        assert_eq!(select.parse_answer("b"), Ok(json!("b")));
+        assert_eq!(
+            select.parse_answer("c"),
+            Err("Must be one of: a, b.".to_string())
+        );
+
+        let optional = input(json!({
+            "type": "select",
+            "options": ["a"],
+            "required": false,
+        }));
+
+        assert_eq!(optional.parse_answer(""), Ok(Value::Null));
+    }
+
+    #[test]
+    fn parses_bool_answers() {
+        let bool_input = input(json!({ "type": "bool" }));
+
+        for answer in ["true", "Yes", "y", "1"] {
+            assert_eq!(bool_input.parse_answer(answer), Ok(json!(true)));
+        }
+
+        for answer in ["false", "NO", "n", "0"] {
+            assert_eq!(bool_input.parse_answer(answer), Ok(json!(false)));
+        }
+
+        assert!(bool_input.parse_answer("maybe").is_err());
+    }
+
+    #[test]
+    fn parses_number_answers() {
+        let number = input(json!({ "type": "number", "min": 1, "max": 10 }));
+
+        assert_eq!(number.parse_answer("3"), Ok(json!(3)));
+        assert_eq!(number.parse_answer("2.5"), Ok(json!(2.5)));
+        assert_eq!(
+            number.parse_answer("0"),
+            Err("Must be at least 1.".to_string())
+        );
+        assert_eq!(
+            number.parse_answer("11"),
+            Err("Must be at most 10.".to_string())
+        );
+        assert_eq!(
+            number.parse_answer("inf"),
+            Err("Must be a number.".to_string())
+        );
+        assert!(number.parse_answer("three").is_err());
+    }
+
+    #[test]
+    fn parses_url_answers() {
+        let url = input(json!({ "type": "url" }));
+
+        assert_eq!(
+            url.parse_answer("https://parra.io/a"),
+            Ok(json!("https://parra.io/a"))
+        );
+        assert_eq!(
+            url.parse_answer("ftp://parra.io"),
+            Err("Must be an http or https URL.".to_string())
+        );
+        assert!(url.parse_answer("parra").is_err());
+    }
+
+    #[test]
+    fn normalizes_color_answers() {
+        let color = input(json!({ "type": "color" }));
+
+        assert_eq!(color.parse_answer("ff8800"), Ok(json!("#FF8800")));
+        assert_eq!(color.parse_answer("#ff880080"), Ok(json!("#FF880080")));
+        assert!(color.parse_answer("#ff880").is_err());
+        assert!(color.parse_answer("orange").is_err());
+    }
+
+    #[test]
+    fn parses_typed_values() {
+        let bool_input = input(json!({ "type": "bool" }));
+        let number = input(json!({ "type": "number", "max": 5 }));
+        let text = input(json!({}));
+
+        assert_eq!(bool_input.parse_value(&json!(true)), Ok(json!(true)));
+        assert_eq!(bool_input.parse_value(&json!("no")), Ok(json!(false)));
+        assert_eq!(number.parse_value(&json!(4.0)), Ok(json!(4)));
+        assert!(number.parse_value(&json!(6)).is_err());
+        assert_eq!(
+            text.parse_value(&json!(1)),
+            Err("Expected a text value.".to_string())
+        );
+        assert!(text.parse_value(&Value::Null).is_err());
+
+        let optional_number =
+            input(json!({ "type": "number", "required": false }));
+
+        assert_eq!(optional_number.parse_value(&Value::Null), Ok(Value::Null));
+    }
+
+    #[test]
+    fn requires_schema_version() {
+        let error = config_error(json!({ "input_groups": [] }));
+
+        assert!(error.starts_with("Missing schema_version."), "{}", error);
+
+        let error = config_error(json!({ "schema_version": 0 }));
+
+        assert!(error.starts_with("Invalid schema_version"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_newer_schema_versions() {
+        let error = config_error(json!({
+            "schema_version": TEMPLATE_CONFIG_SCHEMA_VERSION + 1,
+        }));
+
+        assert!(error.contains("Update the CLI"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_metadata_in_older_schema_versions() {
+        let error = config_error(json!({
+            "schema_version": METADATA_SCHEMA_VERSION - 1,
+            "display_name": "Old",
+        }));
+
+        assert!(error.contains("require schema_version"), "{}", error);
+    }
+
+    #[test]
+    fn accepts_unknown_tab_names() {
+        let config = TemplateConfig::from_json(
+            &json!({
+                "schema_version": TEMPLATE_CONFIG_SCHEMA_VERSION,
+                "required_tabs": ["sample", "forum"],
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(config.required_tabs, vec!["sample", "forum"]);
+    }
+
+    #[test]
+    fn rejects_duplicate_groups() {
+        let error = config_error(config_with_groups(json!([
+            { "name": "shop", "inputs": [] },
+            { "name": "shop", "inputs": [] },
+        ])));
+
+        assert_eq!(
+            error,
+            "The input group \"shop\" is defined more than once."
+        );
+    }
+
+    #[test]
+    fn rejects_reserved_and_invalid_group_names() {
+        for name in RESERVED_INPUT_GROUP_NAMES {
+            let error = config_error(config_with_groups(json!([
+                { "name": name, "inputs": [] },
+            ])));
+
+            assert!(error.contains("is reserved"), "{}", error);
+        }
+
+        let error = config_error(config_with_groups(json!([
+            { "name": "1shop", "inputs": [] },
+        ])));
+
+        assert!(error.starts_with("Invalid input group name"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_forward_condition_references() {
+        let error = config_error(config_with_groups(json!([
+            {
+                "name": "shop",
+                "inputs": [
+                    {
+                        "key": "domain",
+                        "prompt": "Domain?",
+                        "when": { "enabled": true },
+                    },
+                    { "key": "enabled", "prompt": "Enabled?", "type": "bool" },
+                ],
+            },
+        ])));
+
+        assert_eq!(
+            error,
+            "The input \"shop.domain\" depends on \"enabled\", which isn't an earlier input."
+        );
+    }
+
+    #[test]
+    fn accepts_conditions_on_earlier_groups() {
+        let config = TemplateConfig::from_json(
+            &config_with_groups(json!([
+                {
+                    "name": "features",
+                    "inputs": [
+                        { "key": "shop", "prompt": "Shop?", "type": "bool" },
+                    ],
+                },
+                {
+                    "name": "shop",
+                    "inputs": [
+                        {
+                            "key": "domain",
+                            "prompt": "Domain?",
+                            "when": { "features.shop": true },
+                        },
+                    ],
+                },
+            ]))
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(config.input_groups.len(), 2);
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        let error = config_error(config_with_groups(json!([
+            {
+                "name": "shop",
+                "inputs": [{ "key": "kind", "prompt": "?", "type": "select" }],
+            },
+        ])));
+
+        assert_eq!(error, "Select inputs must have options.");
+
+        let error = config_error(config_with_groups(json!([
+            {
+                "name": "shop",
+                "inputs": [{
+                        "key": "count",
+                        "prompt": "?",
+                        "type": "number",
+                        "default": "x",
+                    }],
+            },
+        ])));
+
+        assert!(
+            error.starts_with("Invalid default for shop.count"),
+            "{}",
+            error
+        );
+    }
+}